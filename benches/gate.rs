@@ -0,0 +1,114 @@
+//! Gate latency benchmark
+//!
+//! Runs the `veto` binary the way a hook does (one process per command)
+//! against a generated rules.toml with thousands of rules, and reports
+//! latency with a cold rules cache, a warm cache, and a process baseline.
+//!
+//!     cargo bench --bench gate
+//!     VETO_BENCH_RULES=10000 VETO_BENCH_RUNS=200 cargo bench --bench gate
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const VETO: &str = env!("CARGO_BIN_EXE_veto");
+
+fn env_usize(name: &str, default: usize) -> usize {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Generate a rules file: mostly program-indexed rules plus some
+/// unindexed `*...*` patterns that every command is checked against
+fn write_rules(home: &Path, count: usize) {
+    let mut toml = String::new();
+    let levels = ["critical", "high", "medium", "low"];
+    for i in 0..count {
+        let level = levels[i % levels.len()];
+        let pattern = if i % 10 == 0 {
+            format!("*TOKEN_{}*", i)
+        } else {
+            format!("tool{} --danger-{}*", i % 500, i)
+        };
+        toml.push_str(&format!(
+            "[[{}]]\ncategory = \"bench-{}\"\npatterns = [\"{}\"]\n\n",
+            level, i, pattern
+        ));
+    }
+    std::fs::write(home.join("rules.toml"), toml).expect("write rules.toml");
+}
+
+/// Run one gate invocation with a Claude hook payload
+fn run_gate(home: &Path, command: &str) -> Duration {
+    let payload = serde_json::json!({
+        "tool_name": "Bash",
+        "tool_input": { "command": command },
+    });
+    let start = Instant::now();
+    let mut child = Command::new(VETO)
+        .args(["gate", "--claude"])
+        .env("VETO_HOME", home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("spawn veto");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(payload.to_string().as_bytes())
+        .expect("write stdin");
+    child.wait().expect("wait veto");
+    start.elapsed()
+}
+
+fn run_baseline(home: &Path) -> Duration {
+    let start = Instant::now();
+    Command::new(VETO)
+        .arg("--version")
+        .env("VETO_HOME", home)
+        .stdout(Stdio::null())
+        .status()
+        .expect("run veto");
+    start.elapsed()
+}
+
+fn report(name: &str, mut samples: Vec<Duration>) {
+    samples.sort();
+    let pct = |p: usize| samples[(samples.len() - 1) * p / 100].as_secs_f64() * 1000.0;
+    println!(
+        "{:<24} p50 {:>7.2} ms   p95 {:>7.2} ms   max {:>7.2} ms   ({} runs)",
+        name,
+        pct(50),
+        pct(95),
+        pct(100),
+        samples.len()
+    );
+}
+
+fn main() {
+    let rule_count = env_usize("VETO_BENCH_RULES", 5000);
+    let runs = env_usize("VETO_BENCH_RUNS", 50);
+    let home = tempfile::tempdir().expect("tempdir");
+    write_rules(home.path(), rule_count);
+
+    println!("veto gate latency with {} rules\n", rule_count);
+
+    report("baseline (--version)", (0..runs).map(|_| run_baseline(home.path())).collect());
+
+    let cache = home.path().join("cache").join("rules.json");
+    let cold = (0..runs.min(20))
+        .map(|_| {
+            let _ = std::fs::remove_file(&cache);
+            run_gate(home.path(), "ls -la")
+        })
+        .collect();
+    report("gate, cold cache", cold);
+
+    for command in ["ls -la", "tool42 --danger-42 now", "cargo build --release"] {
+        run_gate(home.path(), command);
+        let warm = (0..runs).map(|_| run_gate(home.path(), command)).collect();
+        report(&format!("gate `{}`", command), warm);
+    }
+}
@@ -1,13 +1,19 @@
 //! Audit logging for veto
 //!
-//! Logs all command evaluations to ~/.veto/audit.log
+//! Logs all command evaluations to ~/.veto/audit.log, and keeps the deny
+//! cache of commands the user rejected in ~/.veto/deny_cache.json
 
 use std::fs::{OpenOptions, create_dir_all};
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 use chrono::Local;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
 
+use crate::auth::{state, AuthError};
 use crate::config::loader::get_config_dir;
-use crate::rules::RiskLevel;
+use crate::rules::{redact_secrets, RiskLevel};
 
 const DENY_CACHE_FILENAME: &str = "deny_cache.json";
 
@@ -65,7 +71,7 @@ fn log_audit_internal(entry: &AuditEntry) -> Result<(), Box<dyn std::error::Erro
         entry.result,
         risk,
         auth,
-        entry.command
+        redact_secrets(&entry.command)
     )?;
 
     Ok(())
@@ -95,48 +101,152 @@ pub fn clear_audit_log() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn get_deny_cache_path() -> std::path::PathBuf {
+fn get_deny_cache_path() -> PathBuf {
     get_config_dir().join(DENY_CACHE_FILENAME)
 }
 
+/// A command the user rejected, blocked from retries in the same session
+/// until it expires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeniedCommand {
+    pub id: String,
+    pub command: String,
+    /// Agent session it was rejected in; `None` outside a session
+    pub session_id: Option<String>,
+    pub level: RiskLevel,
+    pub denied_at: u64,
+    pub expires_at: u64,
+}
+
+impl DeniedCommand {
+    fn blocks(&self, command: &str, session_id: Option<&str>, now: u64) -> bool {
+        now < self.expires_at && self.command == command && self.session_id.as_deref() == session_id
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Unexpired entries; a cache from older versions (a plain list of
+/// commands) reads as empty
+fn load_denied(path: &Path, now: u64) -> Vec<DeniedCommand> {
+    let entries: Vec<DeniedCommand> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    entries.into_iter().filter(|e| now < e.expires_at).collect()
+}
+
+/// Change the unexpired entries under the cache lock, so concurrent hooks
+/// neither lose entries nor see a partly written file
+fn update_denied<R>(
+    path: &Path,
+    now: u64,
+    change: impl FnOnce(&mut Vec<DeniedCommand>) -> R,
+) -> Result<R, AuthError> {
+    state::update(path, |entries: &mut Vec<DeniedCommand>| {
+        entries.retain(|e| now < e.expires_at);
+        Ok(change(entries))
+    })
+}
+
 /// Record a command denial to prevent repeated prompts in hook modes.
-pub fn record_denied_command(command: &str) {
-    if let Err(e) = record_denied_command_internal(command) {
+pub fn record_denied_command(command: &str, level: RiskLevel, session_id: Option<&str>, ttl_minutes: u64) {
+    if let Err(e) = record_denied_at(&get_deny_cache_path(), command, level, session_id, ttl_minutes, now()) {
         eprintln!("Warning: Failed to update deny cache: {}", e);
     }
 }
 
-fn record_denied_command_internal(command: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let cache_path = get_deny_cache_path();
-    create_dir_all(get_config_dir())?;
-
-    let mut entries: Vec<String> = if cache_path.exists() {
-        let content = std::fs::read_to_string(&cache_path)?;
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        vec![]
+fn record_denied_at(
+    path: &Path,
+    command: &str,
+    level: RiskLevel,
+    session_id: Option<&str>,
+    ttl_minutes: u64,
+    now: u64,
+) -> Result<(), AuthError> {
+    let mut id = [0u8; 4];
+    rand::thread_rng().fill_bytes(&mut id);
+    let entry = DeniedCommand {
+        id: id.iter().map(|b| format!("{:02x}", b)).collect(),
+        command: command.to_string(),
+        session_id: session_id.map(String::from),
+        level,
+        denied_at: now,
+        expires_at: now + ttl_minutes.max(1) * 60,
     };
+    update_denied(path, now, |entries| {
+        entries.retain(|e| !e.blocks(command, session_id, now));
+        entries.push(entry);
+    })
+}
 
-    if !entries.iter().any(|c| c == command) {
-        entries.push(command.to_string());
-    }
+/// Check if a command was denied earlier in the same session.
+pub fn was_denied_command(command: &str, session_id: Option<&str>) -> bool {
+    let now = now();
+    load_denied(&get_deny_cache_path(), now).iter().any(|e| e.blocks(command, session_id, now))
+}
 
-    let content = serde_json::to_string_pretty(&entries)?;
-    std::fs::write(cache_path, content)?;
-    Ok(())
+/// Unexpired denials, oldest first
+pub fn denied_commands() -> Vec<DeniedCommand> {
+    load_denied(&get_deny_cache_path(), now())
 }
 
-/// Check if a command was previously denied.
-pub fn was_denied_command(command: &str) -> bool {
-    let cache_path = get_deny_cache_path();
-    if !cache_path.exists() {
-        return false;
-    }
+/// Forget one denial; returns it, or `None` when no entry has that id
+pub fn remove_denied_command(id: &str) -> Result<Option<DeniedCommand>, Box<dyn std::error::Error>> {
+    let removed = update_denied(&get_deny_cache_path(), now(), |entries| {
+        let index = entries.iter().position(|e| e.id == id)?;
+        Some(entries.remove(index))
+    })?;
+    Ok(removed)
+}
 
-    let Ok(content) = std::fs::read_to_string(&cache_path) else {
-        return false;
-    };
+/// Forget every denial; returns how many were active
+pub fn clear_denied_commands() -> Result<usize, Box<dyn std::error::Error>> {
+    let cleared = update_denied(&get_deny_cache_path(), now(), |entries| entries.drain(..).count())?;
+    Ok(cleared)
+}
 
-    let entries: Vec<String> = serde_json::from_str(&content).unwrap_or_default();
-    entries.iter().any(|c| c == command)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denial_scoped_to_session_and_ttl() {
+        let entry = DeniedCommand {
+            id: "0a1b2c3d".to_string(),
+            command: "git push --force".to_string(),
+            session_id: Some("abc".to_string()),
+            level: RiskLevel::High,
+            denied_at: 1000,
+            expires_at: 1060,
+        };
+        assert!(entry.blocks("git push --force", Some("abc"), 1030));
+        assert!(!entry.blocks("git push --force", Some("xyz"), 1030));
+        assert!(!entry.blocks("git push --force", None, 1030));
+        assert!(!entry.blocks("git push", Some("abc"), 1030));
+        assert!(!entry.blocks("git push --force", Some("abc"), 1060));
+
+        // The old format was a list of commands
+        assert!(serde_json::from_str::<Vec<DeniedCommand>>(r#"["git push --force"]"#).is_err());
+    }
+
+    #[test]
+    fn test_parallel_denials_all_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DENY_CACHE_FILENAME);
+
+        std::thread::scope(|s| {
+            for i in 0..8 {
+                let path = &path;
+                s.spawn(move || record_denied_at(path, &format!("rm -rf build{}", i), RiskLevel::High, None, 60, 1000));
+            }
+        });
+        assert_eq!(load_denied(&path, 1001).len(), 8);
+
+        let cleared = update_denied(&path, 1001, |entries| entries.drain(..).count()).unwrap();
+        assert_eq!(cleared, 8);
+        assert!(load_denied(&path, 1001).is_empty());
+    }
 }
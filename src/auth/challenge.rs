@@ -2,59 +2,120 @@
 //!
 //! Generates one-time challenge codes that are sent via notifications
 //! to prevent AI agents from reusing authentication credentials.
+//!
+//! A challenge is bound to its command, working directory and session with
+//! an HMAC-SHA256 under a key held in the keyring. Pending challenges live
+//! in `$VETO_HOME/challenges/`, named by a MAC of the code so the agent
+//! cannot read codes off the directory listing, and are consumed with an
+//! atomic rename so concurrent hook calls cannot both use one.
 
-use std::collections::hash_map::DefaultHasher;
 use std::fs;
-use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use rand::Rng;
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
 
-use super::keyring::SecureKeyring;
-use super::{AuthError, PinAuth};
+use super::keyring::{keys, sign, SecureKeyring};
+use super::{AuthContext, AuthError, PinAuth, TotpAuth};
+use crate::config::loader::{get_config_dir, load_config};
 
 /// Challenge expiration time in seconds
 const CHALLENGE_EXPIRY_SECONDS: u64 = 60;
 
-/// Challenge code length (4 digits)
-const CHALLENGE_LENGTH: usize = 4;
+/// Default challenge code length
+const DEFAULT_LENGTH: usize = 6;
+
+/// Shortest and longest configurable code
+const MIN_LENGTH: usize = 4;
+const MAX_LENGTH: usize = 16;
+
+const DIGITS: &str = "0123456789";
+
+/// Letters and digits that are hard to confuse (no 0/O, 1/I/L)
+const ALPHANUMERIC: &str = "ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// Attempts to find a code no pending challenge uses
+const MAX_GENERATE_ATTEMPTS: usize = 16;
+
+/// Code format from `[auth.challenge]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeSettings {
+    pub length: usize,
+    pub alphabet: Vec<char>,
+}
+
+impl Default for ChallengeSettings {
+    fn default() -> Self {
+        Self { length: DEFAULT_LENGTH, alphabet: DIGITS.chars().collect() }
+    }
+}
+
+impl ChallengeSettings {
+    /// Settings from the user's config
+    pub fn load() -> Self {
+        let challenge = load_config().ok().and_then(|c| c.auth).and_then(|a| a.challenge);
+        let length = challenge.as_ref().and_then(|c| c.length).unwrap_or(DEFAULT_LENGTH);
+        let alphabet = challenge.and_then(|c| c.alphabet);
+        Self::new(length, alphabet.as_deref())
+    }
+
+    /// Length clamped to 4-16; an alphabet with fewer than two distinct
+    /// characters falls back to digits
+    pub fn new(length: usize, alphabet: Option<&str>) -> Self {
+        let chars = match alphabet {
+            None | Some("digits") => DIGITS,
+            Some("alphanumeric") => ALPHANUMERIC,
+            Some(custom) => custom,
+        };
+        let mut alphabet: Vec<char> = Vec::new();
+        for c in chars.chars().filter(|c| !c.is_whitespace()) {
+            if !alphabet.contains(&c) {
+                alphabet.push(c);
+            }
+        }
+        if alphabet.len() < 2 {
+            alphabet = DIGITS.chars().collect();
+        }
+        Self { length: length.clamp(MIN_LENGTH, MAX_LENGTH), alphabet }
+    }
+
+    fn generate_code(&self) -> String {
+        let mut rng = rand::thread_rng();
+        (0..self.length).map(|_| self.alphabet[rng.gen_range(0..self.alphabet.len())]).collect()
+    }
+
+    /// Codes from an alphabet without lowercase letters are case-insensitive
+    fn normalize(&self, code: &str) -> String {
+        if self.alphabet.iter().any(|c| c.is_lowercase()) {
+            code.to_string()
+        } else {
+            code.to_uppercase()
+        }
+    }
+
+    /// Split `<PIN><challenge>` into PIN and code
+    fn split_response<'a>(&self, response: &'a str) -> Option<(&'a str, &'a str)> {
+        let start = response.char_indices().rev().nth(self.length - 1)?.0;
+        (start > 0).then(|| response.split_at(start))
+    }
+}
 
 /// Challenge data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Challenge {
-    /// 4-digit challenge code
+    /// Challenge code; never written to disk
+    #[serde(skip)]
     pub id: String,
-    /// Hash of the command this challenge is bound to
-    pub command_hash: String,
+    /// MAC of the command, working directory and session this challenge
+    /// is bound to
+    pub binding: String,
     /// Unix timestamp when challenge was created
     pub created_at: u64,
-    /// Whether this challenge has been used
-    pub used: bool,
 }
 
 impl Challenge {
-    /// Get the challenges directory path
-    fn challenges_dir() -> Result<PathBuf, AuthError> {
-        let home = dirs::home_dir()
-            .ok_or_else(|| AuthError::Failed("Cannot determine home directory".to_string()))?;
-        Ok(home.join(".veto").join("challenges"))
-    }
-
-    /// Get the path for a specific challenge file
-    fn challenge_path(id: &str) -> Result<PathBuf, AuthError> {
-        let dir = Self::challenges_dir()?;
-        Ok(dir.join(format!("{}.json", id)))
-    }
-
-    /// Hash a command for binding
-    fn hash_command(command: &str) -> String {
-        let mut hasher = DefaultHasher::new();
-        command.hash(&mut hasher);
-        format!("{:016x}", hasher.finish())
-    }
-
     /// Get current Unix timestamp
     fn now() -> u64 {
         SystemTime::now()
@@ -63,127 +124,210 @@ impl Challenge {
             .unwrap_or(0)
     }
 
-    /// Generate a new challenge for a command
-    pub fn generate(command: &str) -> Result<Challenge, AuthError> {
-        // Generate random 4-digit code
-        let mut rng = rand::thread_rng();
-        let code: u32 = rng.gen_range(1000..10000);
-        let id = format!("{:04}", code);
-
-        let challenge = Challenge {
-            id: id.clone(),
-            command_hash: Self::hash_command(command),
-            created_at: Self::now(),
-            used: false,
-        };
+    /// Generate a new challenge for a command run from `context`
+    pub fn generate(command: &str, context: Option<&AuthContext>) -> Result<Challenge, AuthError> {
+        let store = ChallengeStore::open()?;
+        store.create(&ChallengeSettings::load(), command, context)
+    }
 
-        // Ensure challenges directory exists
-        let dir = Self::challenges_dir()?;
-        fs::create_dir_all(&dir)
-            .map_err(|e| AuthError::Failed(format!("Failed to create challenges dir: {}", e)))?;
+    /// Check if this challenge has expired
+    pub fn is_expired(&self) -> bool {
+        let now = Self::now();
+        now.saturating_sub(self.created_at) > CHALLENGE_EXPIRY_SECONDS
+    }
 
-        // Set restrictive permissions on directory
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let perms = fs::Permissions::from_mode(0o700);
-            let _ = fs::set_permissions(&dir, perms);
+    /// Use up this challenge after an approval (dialog, Touch ID,
+    /// Telegram); fails if it was already used
+    pub fn consume(&self) -> Result<(), AuthError> {
+        ChallengeStore::open()?.consume(&self.id).map_err(AuthError::Failed)
+    }
+
+    /// Drop this challenge without using it
+    pub fn discard(&self) {
+        if let Ok(store) = ChallengeStore::open() {
+            let _ = fs::remove_file(store.path(&self.id));
         }
+    }
 
-        // Save challenge to file
-        let path = Self::challenge_path(&id)?;
-        let json = serde_json::to_string_pretty(&challenge)
-            .map_err(|e| AuthError::Failed(format!("Failed to serialize challenge: {}", e)))?;
+    /// Clean up expired challenges
+    pub fn cleanup_expired() -> Result<usize, AuthError> {
+        Ok(ChallengeStore::open()?.cleanup_expired())
+    }
+}
 
-        fs::write(&path, json)
-            .map_err(|e| AuthError::Failed(format!("Failed to save challenge: {}", e)))?;
+/// Methods whose code is combined with the challenge in one response
+/// (`<PIN><challenge>`, `<TOTP><challenge>`, `<challenge>`)
+pub fn takes_response(method: &str) -> bool {
+    matches!(method, "pin" | "totp" | "confirm")
+}
 
-        Ok(challenge)
+/// Pending challenges on disk and the key that names and binds them
+struct ChallengeStore {
+    dir: PathBuf,
+    key: Vec<u8>,
+}
+
+impl ChallengeStore {
+    fn open() -> Result<Self, AuthError> {
+        let key = SecureKeyring::signing_key(keys::CHALLENGE_KEY)
+            .map_err(|e| AuthError::Failed(format!("Failed to load challenge key: {}", e)))?;
+        Ok(Self { dir: get_config_dir().join("challenges"), key })
     }
 
-    /// Load a challenge by ID
-    pub fn load(id: &str) -> Result<Challenge, AuthError> {
-        let path = Self::challenge_path(id)?;
-        let json = fs::read_to_string(&path)
-            .map_err(|_| AuthError::Failed(format!("Challenge {} not found", id)))?;
+    fn mac(&self, parts: &[&str]) -> String {
+        sign(&self.key, parts)
+    }
+
+    fn binding(&self, command: &str, context: Option<&AuthContext>) -> String {
+        let cwd = context.and_then(|c| c.cwd.as_deref()).unwrap_or("");
+        let session = context.and_then(|c| c.session_id.as_deref()).unwrap_or("");
+        self.mac(&["binding", command, cwd, session])
+    }
 
-        serde_json::from_str(&json)
-            .map_err(|e| AuthError::Failed(format!("Failed to parse challenge: {}", e)))
+    fn path(&self, code: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", self.mac(&["code", code])))
     }
 
-    /// Mark a challenge as used and save
-    pub fn mark_used(&mut self) -> Result<(), AuthError> {
-        self.used = true;
-        let path = Self::challenge_path(&self.id)?;
-        let json = serde_json::to_string_pretty(self)
+    /// Save a challenge under a code no pending challenge uses. The file is
+    /// written aside and hard-linked into place, which fails rather than
+    /// replacing an existing one.
+    fn create(
+        &self,
+        settings: &ChallengeSettings,
+        command: &str,
+        context: Option<&AuthContext>,
+    ) -> Result<Challenge, AuthError> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| AuthError::Failed(format!("Failed to create challenges dir: {}", e)))?;
+
+        // Set restrictive permissions on directory
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let perms = fs::Permissions::from_mode(0o700);
+            let _ = fs::set_permissions(&self.dir, perms);
+        }
+
+        let mut challenge = Challenge {
+            id: String::new(),
+            binding: self.binding(command, context),
+            created_at: Challenge::now(),
+        };
+        let json = serde_json::to_string_pretty(&challenge)
             .map_err(|e| AuthError::Failed(format!("Failed to serialize challenge: {}", e)))?;
+        let staging = self.dir.join(format!(".{}.tmp", unique_suffix()));
+        fs::write(&staging, json).map_err(|e| AuthError::Failed(format!("Failed to save challenge: {}", e)))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&staging, fs::Permissions::from_mode(0o600));
+        }
 
-        fs::write(&path, json)
-            .map_err(|e| AuthError::Failed(format!("Failed to save challenge: {}", e)))
+        let mut result = Err(AuthError::Failed("No free challenge code; try again".to_string()));
+        for _ in 0..MAX_GENERATE_ATTEMPTS {
+            let code = settings.generate_code();
+            match fs::hard_link(&staging, self.path(&code)) {
+                Ok(()) => {
+                    challenge.id = code;
+                    result = Ok(challenge);
+                    break;
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    result = Err(AuthError::Failed(format!("Failed to save challenge: {}", e)));
+                    break;
+                }
+            }
+        }
+        let _ = fs::remove_file(&staging);
+        result
     }
 
-    /// Delete this challenge file
-    pub fn delete(&self) -> Result<(), AuthError> {
-        let path = Self::challenge_path(&self.id)?;
-        fs::remove_file(&path)
-            .map_err(|e| AuthError::Failed(format!("Failed to delete challenge: {}", e)))
-    }
+    /// The pending challenge for a code, checked against the command
+    fn check(&self, code: &str, command: &str, context: Option<&AuthContext>) -> Result<Challenge, String> {
+        let json = fs::read_to_string(self.path(code)).map_err(|_| "Invalid or already used challenge code".to_string())?;
+        let mut challenge: Challenge =
+            serde_json::from_str(&json).map_err(|e| format!("Failed to parse challenge: {}", e))?;
+        challenge.id = code.to_string();
 
-    /// Check if this challenge has expired
-    pub fn is_expired(&self) -> bool {
-        let now = Self::now();
-        now.saturating_sub(self.created_at) > CHALLENGE_EXPIRY_SECONDS
+        if challenge.is_expired() {
+            let _ = fs::remove_file(self.path(code));
+            return Err("Challenge expired".to_string());
+        }
+        if challenge.binding != self.binding(command, context) {
+            return Err("Challenge not valid for this command".to_string());
+        }
+        Ok(challenge)
     }
 
-    /// Check if this challenge is valid for the given command
-    pub fn is_valid_for_command(&self, command: &str) -> bool {
-        self.command_hash == Self::hash_command(command)
+    /// Use up a challenge. Only one caller can rename the file away, so
+    /// the code is accepted at most once even under concurrent hooks.
+    fn consume(&self, code: &str) -> Result<(), String> {
+        let claimed = self.dir.join(format!(".{}.used", unique_suffix()));
+        fs::rename(self.path(code), &claimed).map_err(|_| "Challenge already used".to_string())?;
+        let _ = fs::remove_file(claimed);
+        Ok(())
     }
 
-    /// Clean up expired challenges
-    pub fn cleanup_expired() -> Result<usize, AuthError> {
-        let dir = Self::challenges_dir()?;
-        if !dir.exists() {
-            return Ok(0);
-        }
-
+    fn cleanup_expired(&self) -> usize {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return 0;
+        };
         let mut count = 0;
-        if let Ok(entries) = fs::read_dir(&dir) {
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".json") {
-                        let id = name.trim_end_matches(".json");
-                        if let Ok(challenge) = Self::load(id) {
-                            if challenge.is_expired() || challenge.used {
-                                let _ = challenge.delete();
-                                count += 1;
-                            }
-                        }
-                    }
-                }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let expired = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<Challenge>(&json).ok())
+                .is_none_or(|challenge| challenge.is_expired());
+            // Leftover staging files are only removed once stale
+            let stale = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.elapsed().ok())
+                .is_some_and(|age| age.as_secs() > CHALLENGE_EXPIRY_SECONDS);
+            if expired && (path.extension().is_some_and(|e| e == "json") || stale) && fs::remove_file(&path).is_ok() {
+                count += 1;
             }
         }
-        Ok(count)
+        count
     }
 }
 
-/// Send challenge notification via available channels
+fn unique_suffix() -> String {
+    format!("{}-{:016x}", std::process::id(), rand::thread_rng().next_u64())
+}
+
 /// Send challenge notification via available channels
 /// At least one notification method must succeed
 pub fn notify_challenge(challenge: &Challenge, command: &str) -> Result<(), AuthError> {
+    let message = format!(
+        "🔐 <b>Veto Challenge Code</b>\n\n\
+         <b>Code:</b> <code>{}</code>\n\n\
+         <b>Command:</b>\n<code>{}</code>\n\n\
+         <i>Expires in {} seconds</i>",
+        challenge.id,
+        html_escape(&crate::rules::redact_secrets(command)),
+        CHALLENGE_EXPIRY_SECONDS
+    );
+    notify("veto Challenge", &format!("Challenge code: {}", challenge.id), &message)
+}
+
+/// Notify the user on the desktop and, if configured, on Telegram
+/// (`telegram` is HTML). At least one channel must succeed.
+pub fn notify(title: &str, body: &str, telegram: &str) -> Result<(), AuthError> {
     let mut desktop_ok = false;
     let mut telegram_ok = false;
 
     // Try desktop notification (macOS/Linux)
-    if let Ok(()) = send_desktop_notification(challenge) {
+    if let Ok(()) = send_desktop_notification(title, body) {
         desktop_ok = true;
     }
 
     // Try Telegram notification if configured
-    if SecureKeyring::has_telegram() {
-        if send_telegram_notification(challenge, command).is_ok() {
-            telegram_ok = true;
-        }
+    if SecureKeyring::has_telegram() && send_telegram_message(telegram).is_ok() {
+        telegram_ok = true;
     }
 
     // At least one notification method must succeed
@@ -197,12 +341,13 @@ pub fn notify_challenge(challenge: &Challenge, command: &str) -> Result<(), Auth
 }
 
 /// Send desktop notification (macOS: osascript, Linux: notify-send)
-fn send_desktop_notification(challenge: &Challenge) -> Result<(), AuthError> {
+fn send_desktop_notification(title: &str, body: &str) -> Result<(), AuthError> {
     #[cfg(target_os = "macos")]
     {
         let script = format!(
-            r#"display notification "Challenge: {}" with title "veto" sound name "Glass""#,
-            challenge.id
+            r#"display notification "{}" with title "{}" sound name "Glass""#,
+            body.replace('"', "'"),
+            title.replace('"', "'")
         );
 
         std::process::Command::new("osascript")
@@ -217,12 +362,7 @@ fn send_desktop_notification(challenge: &Challenge) -> Result<(), AuthError> {
     {
         // Try notify-send (libnotify) for Linux desktop notifications
         let result = std::process::Command::new("notify-send")
-            .args([
-                "--urgency=critical",
-                "--app-name=veto",
-                "veto Challenge",
-                &format!("Challenge code: {}", challenge.id),
-            ])
+            .args(["--urgency=critical", "--app-name=veto", title, body])
             .output();
 
         match result {
@@ -240,14 +380,15 @@ fn send_desktop_notification(challenge: &Challenge) -> Result<(), AuthError> {
     #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         // Other platforms: require Telegram
+        let _ = (title, body);
         Err(AuthError::Failed(
             "Desktop notifications not supported. Configure Telegram for challenge notifications.".to_string()
         ))
     }
 }
 
-/// Send Telegram notification (async wrapper)
-fn send_telegram_notification(challenge: &Challenge, command: &str) -> Result<(), AuthError> {
+/// Send an HTML message to the configured Telegram chat
+fn send_telegram_message(message: &str) -> Result<(), AuthError> {
     use crate::config::loader::load_config;
 
     let config = load_config().map_err(|e| AuthError::Failed(e.to_string()))?;
@@ -264,19 +405,10 @@ fn send_telegram_notification(challenge: &Challenge, command: &str) -> Result<()
 
     // Use blocking reqwest since we're in sync context
     let client = reqwest::blocking::Client::new();
-    let message = format!(
-        "🔐 <b>Veto Challenge Code</b>\n\n\
-         <b>Code:</b> <code>{}</code>\n\n\
-         <b>Command:</b>\n<code>{}</code>\n\n\
-         <i>Expires in 60 seconds</i>",
-        challenge.id,
-        html_escape(command)
-    );
-
     let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
     let params = [
         ("chat_id", chat_id.as_str()),
-        ("text", &message),
+        ("text", message),
         ("parse_mode", "HTML"),
     ];
 
@@ -290,7 +422,7 @@ fn send_telegram_notification(challenge: &Challenge, command: &str) -> Result<()
 }
 
 /// Escape HTML special characters for Telegram
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -308,156 +440,111 @@ pub struct ChallengeVerifyResult {
 
 /// Verify a response that contains challenge code
 ///
-/// For PIN auth: response = PIN + challenge (e.g., "12344827" = PIN "1234" + challenge "4827")
-/// For confirm auth: response = challenge only (e.g., "4827")
+/// For PIN auth: response = PIN + challenge (e.g., "1234482731" = PIN "1234" + challenge "482731")
+/// For TOTP auth: response = TOTP code + challenge (e.g., "123456482731")
+/// For confirm auth: response = challenge only (e.g., "482731")
 pub fn verify_response(
     response: &str,
     command: &str,
+    context: Option<&AuthContext>,
     auth_method: &str,
 ) -> ChallengeVerifyResult {
+    let store = match ChallengeStore::open() {
+        Ok(store) => store,
+        Err(e) => return failed(auth_method, e.to_string()),
+    };
     // Clean up expired challenges first
-    let _ = Challenge::cleanup_expired();
+    store.cleanup_expired();
 
+    let settings = ChallengeSettings::load();
     match auth_method {
-        "pin" => verify_pin_with_challenge(response, command),
-        "confirm" => verify_confirm_with_challenge(response, command),
-        _ => ChallengeVerifyResult {
-            success: false,
-            method: auth_method.to_string(),
-            error: Some(format!("Unsupported auth method for challenge: {}", auth_method)),
-        },
+        "pin" => verify_pin_with_challenge(&store, &settings, response, command, context),
+        "totp" => verify_totp_with_challenge(&store, &settings, response, command, context),
+        "confirm" => verify_confirm_with_challenge(&store, &settings, response, command, context),
+        _ => failed(auth_method, format!("Unsupported auth method for challenge: {}", auth_method)),
     }
 }
 
+fn failed(method: &str, error: String) -> ChallengeVerifyResult {
+    ChallengeVerifyResult { success: false, method: method.to_string(), error: Some(error) }
+}
+
 /// Verify PIN + challenge response
-/// Format: {PIN}{4-digit challenge} e.g., "12344827"
-fn verify_pin_with_challenge(response: &str, command: &str) -> ChallengeVerifyResult {
-    if response.len() < CHALLENGE_LENGTH + 1 {
-        return ChallengeVerifyResult {
-            success: false,
-            method: "PIN".to_string(),
-            error: Some("Response too short (need PIN + 4-digit challenge)".to_string()),
-        };
+/// Format: {PIN}{challenge}
+fn verify_pin_with_challenge(
+    store: &ChallengeStore,
+    settings: &ChallengeSettings,
+    response: &str,
+    command: &str,
+    context: Option<&AuthContext>,
+) -> ChallengeVerifyResult {
+    let Some((pin, code)) = settings.split_response(response) else {
+        return failed("PIN", format!("Response too short (need PIN + {}-character challenge)", settings.length));
+    };
+    let code = settings.normalize(code);
+
+    if let Err(error) = store.check(&code, command, context) {
+        return failed("PIN", error);
     }
 
-    // Extract challenge from last 4 characters
-    let challenge_start = response.len() - CHALLENGE_LENGTH;
-    let challenge_id = &response[challenge_start..];
-    let pin = &response[..challenge_start];
-
-    // Verify challenge
-    match Challenge::load(challenge_id) {
-        Ok(mut challenge) => {
-            if challenge.used {
-                return ChallengeVerifyResult {
-                    success: false,
-                    method: "PIN".to_string(),
-                    error: Some("Challenge already used".to_string()),
-                };
-            }
+    // Verify PIN before using up the challenge, so a mistyped PIN can be
+    // retried with the same code
+    match PinAuth::new().verify_direct(pin) {
+        Ok(true) => match store.consume(&code) {
+            Ok(()) => ChallengeVerifyResult { success: true, method: "PIN+challenge".to_string(), error: None },
+            Err(error) => failed("PIN", error),
+        },
+        Ok(false) => failed("PIN", "Invalid PIN".to_string()),
+        Err(e) => failed("PIN", format!("PIN verification error: {}", e)),
+    }
+}
 
-            if challenge.is_expired() {
-                let _ = challenge.delete();
-                return ChallengeVerifyResult {
-                    success: false,
-                    method: "PIN".to_string(),
-                    error: Some("Challenge expired".to_string()),
-                };
-            }
+/// Verify TOTP + challenge response
+/// Format: {TOTP code}{challenge}
+fn verify_totp_with_challenge(
+    store: &ChallengeStore,
+    settings: &ChallengeSettings,
+    response: &str,
+    command: &str,
+    context: Option<&AuthContext>,
+) -> ChallengeVerifyResult {
+    let Some((totp, code)) = settings.split_response(response) else {
+        return failed("TOTP", format!("Response too short (need TOTP code + {}-character challenge)", settings.length));
+    };
+    let code = settings.normalize(code);
 
-            if !challenge.is_valid_for_command(command) {
-                return ChallengeVerifyResult {
-                    success: false,
-                    method: "PIN".to_string(),
-                    error: Some("Challenge not valid for this command".to_string()),
-                };
-            }
+    if let Err(error) = store.check(&code, command, context) {
+        return failed("TOTP", error);
+    }
 
-            // Verify PIN
-            let pin_auth = PinAuth::new();
-            match pin_auth.verify_direct(pin) {
-                Ok(true) => {
-                    // Mark challenge as used
-                    let _ = challenge.mark_used();
-                    ChallengeVerifyResult {
-                        success: true,
-                        method: "PIN+challenge".to_string(),
-                        error: None,
-                    }
-                }
-                Ok(false) => ChallengeVerifyResult {
-                    success: false,
-                    method: "PIN".to_string(),
-                    error: Some("Invalid PIN".to_string()),
-                },
-                Err(e) => ChallengeVerifyResult {
-                    success: false,
-                    method: "PIN".to_string(),
-                    error: Some(format!("PIN verification error: {}", e)),
-                },
-            }
-        }
-        Err(_) => ChallengeVerifyResult {
-            success: false,
-            method: "PIN".to_string(),
-            error: Some("Invalid challenge code".to_string()),
+    match TotpAuth::verify_for(totp, Some(command)) {
+        Ok(true) => match store.consume(&code) {
+            Ok(()) => ChallengeVerifyResult { success: true, method: "TOTP+challenge".to_string(), error: None },
+            Err(error) => failed("TOTP", error),
         },
+        Ok(false) => failed("TOTP", "Invalid TOTP code".to_string()),
+        Err(e) => failed("TOTP", format!("TOTP verification error: {}", e)),
     }
 }
 
 /// Verify confirm with challenge response
-/// Format: just the 4-digit challenge e.g., "4827"
-fn verify_confirm_with_challenge(response: &str, command: &str) -> ChallengeVerifyResult {
-    let challenge_id = response.trim();
-
-    if challenge_id.len() != CHALLENGE_LENGTH {
-        return ChallengeVerifyResult {
-            success: false,
-            method: "confirm".to_string(),
-            error: Some(format!("Challenge must be {} digits", CHALLENGE_LENGTH)),
-        };
-    }
-
-    match Challenge::load(challenge_id) {
-        Ok(mut challenge) => {
-            if challenge.used {
-                return ChallengeVerifyResult {
-                    success: false,
-                    method: "confirm".to_string(),
-                    error: Some("Challenge already used".to_string()),
-                };
-            }
-
-            if challenge.is_expired() {
-                let _ = challenge.delete();
-                return ChallengeVerifyResult {
-                    success: false,
-                    method: "confirm".to_string(),
-                    error: Some("Challenge expired".to_string()),
-                };
-            }
+/// Format: just the challenge
+fn verify_confirm_with_challenge(
+    store: &ChallengeStore,
+    settings: &ChallengeSettings,
+    response: &str,
+    command: &str,
+    context: Option<&AuthContext>,
+) -> ChallengeVerifyResult {
+    let code = settings.normalize(response.trim());
 
-            if !challenge.is_valid_for_command(command) {
-                return ChallengeVerifyResult {
-                    success: false,
-                    method: "confirm".to_string(),
-                    error: Some("Challenge not valid for this command".to_string()),
-                };
-            }
+    if code.chars().count() != settings.length {
+        return failed("confirm", format!("Challenge must be {} characters", settings.length));
+    }
 
-            // Mark challenge as used
-            let _ = challenge.mark_used();
-            ChallengeVerifyResult {
-                success: true,
-                method: "challenge".to_string(),
-                error: None,
-            }
-        }
-        Err(_) => ChallengeVerifyResult {
-            success: false,
-            method: "confirm".to_string(),
-            error: Some("Invalid challenge code".to_string()),
-        },
+    match store.check(&code, command, context).and_then(|_| store.consume(&code)) {
+        Ok(()) => ChallengeVerifyResult { success: true, method: "challenge".to_string(), error: None },
+        Err(error) => failed("confirm", error),
     }
 }
 
@@ -465,43 +552,56 @@ fn verify_confirm_with_challenge(response: &str, command: &str) -> ChallengeVeri
 mod tests {
     use super::*;
 
+    fn store() -> (tempfile::TempDir, ChallengeStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ChallengeStore { dir: dir.path().join("challenges"), key: b"test-key".to_vec() };
+        (dir, store)
+    }
+
     #[test]
     fn test_challenge_generation() {
-        let challenge = Challenge::generate("test command").expect("Failed to generate challenge");
+        let (_dir, store) = store();
+        let settings = ChallengeSettings::default();
+        let challenge = store.create(&settings, "test command", None).expect("Failed to generate challenge");
 
-        assert_eq!(challenge.id.len(), 4);
-        assert!(!challenge.used);
+        assert_eq!(challenge.id.len(), DEFAULT_LENGTH);
+        assert!(challenge.id.chars().all(|c| c.is_ascii_digit()));
         assert!(!challenge.is_expired());
 
-        // Cleanup
-        let _ = challenge.delete();
+        // The code is not readable from the directory
+        let names: Vec<String> = fs::read_dir(&store.dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names.len(), 1);
+        assert!(!names[0].contains(&challenge.id));
+        assert!(!fs::read_to_string(store.path(&challenge.id)).unwrap().contains(&challenge.id));
     }
 
     #[test]
-    fn test_challenge_command_binding() {
-        let challenge = Challenge::generate("rm -rf /").expect("Failed to generate challenge");
-
-        assert!(challenge.is_valid_for_command("rm -rf /"));
-        assert!(!challenge.is_valid_for_command("rm -rf ~"));
-
-        // Cleanup
-        let _ = challenge.delete();
+    fn test_challenge_settings() {
+        let settings = ChallengeSettings::new(2, Some("alphanumeric"));
+        assert_eq!(settings.length, MIN_LENGTH);
+        assert_eq!(settings.normalize("ab2c"), "AB2C");
+        assert_eq!(ChallengeSettings::new(8, Some("aa")).alphabet, ChallengeSettings::default().alphabet);
+
+        let settings = ChallengeSettings::default();
+        assert_eq!(settings.split_response("1234482731"), Some(("1234", "482731")));
+        assert_eq!(settings.split_response("482731"), None);
     }
 
     #[test]
-    fn test_challenge_single_use() {
-        let mut challenge = Challenge::generate("test").expect("Failed to generate challenge");
-        let id = challenge.id.clone();
-
-        // Mark as used
-        challenge.mark_used().expect("Failed to mark used");
-
-        // Load and verify it's marked as used
-        let loaded = Challenge::load(&id).expect("Failed to load challenge");
-        assert!(loaded.used);
-
-        // Cleanup
-        let _ = challenge.delete();
+    fn test_challenge_context_binding() {
+        let (_dir, store) = store();
+        let settings = ChallengeSettings::default();
+        let context = AuthContext::new().with_cwd("/work").with_session_id("abc");
+        let challenge = store.create(&settings, "rm -rf /", Some(&context)).unwrap();
+
+        assert!(store.check(&challenge.id, "rm -rf /", Some(&context)).is_ok());
+        assert!(store.check(&challenge.id, "rm -rf ~", Some(&context)).is_err());
+        let other_session = AuthContext::new().with_cwd("/work").with_session_id("xyz");
+        assert!(store.check(&challenge.id, "rm -rf /", Some(&other_session)).is_err());
+        assert!(store.check(&challenge.id, "rm -rf /", None).is_err());
     }
 
     #[test]
@@ -511,30 +611,46 @@ mod tests {
 
     #[test]
     fn test_verify_confirm_with_challenge() {
-        let challenge = Challenge::generate("echo test").expect("Failed to generate challenge");
-        let id = challenge.id.clone();
+        let (_dir, store) = store();
+        let settings = ChallengeSettings::default();
+        let challenge = store.create(&settings, "echo test", None).unwrap();
 
         // Verify with correct challenge
-        let result = verify_confirm_with_challenge(&id, "echo test");
+        let result = verify_confirm_with_challenge(&store, &settings, &challenge.id, "echo test", None);
         assert!(result.success);
 
         // Try to use again - should fail
-        let result2 = verify_confirm_with_challenge(&id, "echo test");
+        let result2 = verify_confirm_with_challenge(&store, &settings, &challenge.id, "echo test", None);
         assert!(!result2.success);
         assert!(result2.error.unwrap().contains("already used"));
     }
 
     #[test]
     fn test_verify_confirm_wrong_command() {
-        let challenge = Challenge::generate("echo test").expect("Failed to generate challenge");
-        let id = challenge.id.clone();
+        let (_dir, store) = store();
+        let settings = ChallengeSettings::default();
+        let challenge = store.create(&settings, "echo test", None).unwrap();
 
         // Verify with wrong command
-        let result = verify_confirm_with_challenge(&id, "echo other");
+        let result = verify_confirm_with_challenge(&store, &settings, &challenge.id, "echo other", None);
         assert!(!result.success);
         assert!(result.error.unwrap().contains("not valid for this command"));
+    }
 
-        // Cleanup
-        let _ = challenge.delete();
+    #[test]
+    fn test_concurrent_consumption_is_single_use() {
+        let (_dir, store) = store();
+        let challenge = store.create(&ChallengeSettings::default(), "deploy", None).unwrap();
+        let store = std::sync::Arc::new(store);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let store = store.clone();
+                let code = challenge.id.clone();
+                std::thread::spawn(move || store.consume(&code).is_ok())
+            })
+            .collect();
+        let used = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+        assert_eq!(used, 1);
     }
 }
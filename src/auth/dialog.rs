@@ -5,6 +5,7 @@
 
 use std::process::Command;
 use super::{AuthResult, AuthError, Authenticator, AuthContext};
+use crate::rules::redact_secrets;
 
 pub struct DialogAuth;
 
@@ -19,8 +20,8 @@ impl DialogAuth {
             return Err(AuthError::NotAvailable("Dialog auth is only available on macOS".to_string()));
         }
 
-        // Escape command for AppleScript
-        let escaped_cmd = command
+        // Escape command for AppleScript (secrets are never shown on screen)
+        let escaped_cmd = redact_secrets(command)
             .replace('\\', "\\\\")
             .replace('"', "\\\"");
 
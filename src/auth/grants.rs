@@ -0,0 +1,459 @@
+//! Time-limited approval grants
+//!
+//! An approval of a command matching one of the `[auth.grants]` patterns
+//! mints a grant: later commands matching the same pattern, in the same
+//! working directory tree and agent session, pass without a prompt until
+//! the grant expires or is used up. Grants are kept in
+//! `~/.veto/grants.json`, each signed with a keyring key so an edited or
+//! forged entry is ignored. The file carries a generation, bumped on every
+//! write and mirrored in the keyring, so a restored older copy (with its
+//! spent uses) is ignored too.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::keyring::{keys, sign, SecureKeyring};
+use super::state::{self, Generation};
+use super::{AuthContext, AuthError};
+use crate::config::loader::{get_config_dir, load_config};
+use crate::config::Config;
+use crate::rules::RiskLevel;
+
+const DEFAULT_TTL_MINUTES: u64 = 15;
+const DEFAULT_MAX_USES: u32 = 10;
+
+/// Shell syntax that chains or nests commands; a grant never covers it
+const COMPOUND_SYNTAX: &[&str] = &[";", "&", "|", "`", "$(", "<(", ">(", "\n"];
+
+/// A standing approval for commands matching `pattern`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Grant {
+    pub id: String,
+    /// Glob over the command (`cargo publish*`)
+    pub pattern: String,
+    /// Directory the grant applies in (and below); `None` for anywhere
+    pub cwd: Option<String>,
+    /// Agent session the grant applies to; `None` for any
+    pub session_id: Option<String>,
+    /// Highest risk level covered
+    pub level: RiskLevel,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub max_uses: u32,
+    pub uses: u32,
+    signature: String,
+}
+
+impl Grant {
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn remaining_uses(&self) -> u32 {
+        self.max_uses.saturating_sub(self.uses)
+    }
+
+    /// Whether the grant allows `command` at `level` in `scope`
+    fn covers(&self, command: &str, level: RiskLevel, scope: &GrantScope, now: u64) -> bool {
+        if self.is_expired(now) || self.remaining_uses() == 0 || level > self.level {
+            return false;
+        }
+        if let Some(session) = &self.session_id {
+            if scope.session_id.as_ref() != Some(session) {
+                return false;
+            }
+        }
+        if let Some(cwd) = &self.cwd {
+            if !scope.cwd.as_ref().is_some_and(|dir| Path::new(dir).starts_with(cwd)) {
+                return false;
+            }
+        }
+        is_grantable(command) && pattern_matches(&self.pattern, command)
+    }
+
+    /// Every field but the signature, plus the file generation, in a
+    /// fixed order
+    fn signed_parts(&self, generation: u64) -> Vec<String> {
+        vec![
+            "grant".to_string(),
+            generation.to_string(),
+            self.id.clone(),
+            self.pattern.clone(),
+            self.cwd.clone().unwrap_or_default(),
+            self.session_id.clone().unwrap_or_default(),
+            self.level.to_string(),
+            self.created_at.to_string(),
+            self.expires_at.to_string(),
+            self.max_uses.to_string(),
+            self.uses.to_string(),
+        ]
+    }
+}
+
+/// Where a command runs, as far as grants are concerned
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GrantScope {
+    pub cwd: Option<String>,
+    pub session_id: Option<String>,
+}
+
+impl GrantScope {
+    /// Scope from a hook's context, falling back to the current directory
+    pub fn from_context(context: Option<&AuthContext>) -> Self {
+        let cwd = context.and_then(|c| c.cwd.clone()).or_else(|| {
+            std::env::current_dir().ok().map(|dir| dir.to_string_lossy().into_owned())
+        });
+        Self { cwd, session_id: context.and_then(|c| c.session_id.clone()) }
+    }
+}
+
+/// Settings from `[auth.grants]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantPolicy {
+    /// Patterns an approval mints a grant for
+    pub patterns: Vec<String>,
+    pub ttl_minutes: u64,
+    pub max_uses: u32,
+    pub allow_critical: bool,
+}
+
+impl Default for GrantPolicy {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            ttl_minutes: DEFAULT_TTL_MINUTES,
+            max_uses: DEFAULT_MAX_USES,
+            allow_critical: false,
+        }
+    }
+}
+
+impl GrantPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        let grants = config.auth.as_ref().and_then(|a| a.grants.as_ref());
+        let defaults = Self::default();
+        Self {
+            patterns: grants.and_then(|g| g.patterns.clone()).unwrap_or_default(),
+            ttl_minutes: grants.and_then(|g| g.ttl_minutes).unwrap_or(defaults.ttl_minutes).max(1),
+            max_uses: grants.and_then(|g| g.max_uses).unwrap_or(defaults.max_uses).max(1),
+            allow_critical: grants.and_then(|g| g.allow_critical).unwrap_or(defaults.allow_critical),
+        }
+    }
+
+    /// Policy from the user's config
+    pub fn load() -> Self {
+        Self::from_config(&load_config().unwrap_or_default())
+    }
+
+    /// Whether grants may cover commands at `level`
+    pub fn allows_level(&self, level: RiskLevel) -> bool {
+        level != RiskLevel::Critical || self.allow_critical
+    }
+
+    /// The configured pattern an approval of `command` mints a grant for
+    pub fn pattern_for(&self, command: &str) -> Option<&str> {
+        if !is_grantable(command) {
+            return None;
+        }
+        self.patterns.iter().map(String::as_str).find(|pattern| pattern_matches(pattern, command))
+    }
+}
+
+/// Commands chaining or nesting others are never covered: `cargo publish*`
+/// must not allow `cargo publish; rm -rf ~`
+pub fn is_grantable(command: &str) -> bool {
+    !COMPOUND_SYNTAX.iter().any(|syntax| command.contains(syntax))
+}
+
+fn pattern_matches(pattern: &str, command: &str) -> bool {
+    glob::Pattern::new(pattern).is_ok_and(|p| p.matches(command.trim()))
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Use up one run of a grant covering `command`, if there is one
+pub fn redeem(command: &str, level: RiskLevel, scope: &GrantScope, policy: &GrantPolicy) -> Option<Grant> {
+    if !policy.allows_level(level) || !grants_path().exists() {
+        return None;
+    }
+    GrantStore::open().ok()?.redeem(command, level, scope, now()).ok().flatten()
+}
+
+/// After an approval, mint a grant when `command` matches a configured
+/// pattern
+pub fn mint(command: &str, level: RiskLevel, scope: &GrantScope, policy: &GrantPolicy) -> Result<Option<Grant>, AuthError> {
+    let Some(pattern) = policy.pattern_for(command) else {
+        return Ok(None);
+    };
+    if !policy.allows_level(level) {
+        return Ok(None);
+    }
+    add(pattern, level, scope, policy.ttl_minutes, policy.max_uses).map(Some)
+}
+
+/// Add a grant for `pattern` up to `level`
+pub fn add(pattern: &str, level: RiskLevel, scope: &GrantScope, ttl_minutes: u64, max_uses: u32) -> Result<Grant, AuthError> {
+    glob::Pattern::new(pattern).map_err(|e| AuthError::Failed(format!("Invalid pattern '{}': {}", pattern, e)))?;
+    if !is_grantable(pattern) {
+        return Err(AuthError::Failed(format!("Pattern '{}' chains commands", pattern)));
+    }
+    GrantStore::open()?.add(pattern, level, scope, ttl_minutes, max_uses, now())
+}
+
+/// Active grants, soonest to expire first
+pub fn list() -> Vec<Grant> {
+    if !grants_path().exists() {
+        return Vec::new();
+    }
+    let Ok(store) = GrantStore::open() else {
+        return Vec::new();
+    };
+    let mut grants = store.load(now());
+    grants.sort_by_key(|g| g.expires_at);
+    grants
+}
+
+/// Revoke one grant; returns false when there is no grant with that id
+pub fn revoke(id: &str) -> Result<bool, AuthError> {
+    if !grants_path().exists() {
+        return Ok(false);
+    }
+    GrantStore::open()?.revoke(Some(id), now()).map(|removed| removed > 0)
+}
+
+/// Revoke every grant; returns how many were active
+pub fn revoke_all() -> Result<usize, AuthError> {
+    if !grants_path().exists() {
+        return Ok(0);
+    }
+    GrantStore::open()?.revoke(None, now())
+}
+
+fn grants_path() -> PathBuf {
+    get_config_dir().join("grants.json")
+}
+
+/// Contents of the grants file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GrantFile {
+    generation: u64,
+    grants: Vec<Grant>,
+}
+
+/// The grants file, the key its entries are signed with and its current
+/// generation
+struct GrantStore {
+    path: PathBuf,
+    key: Vec<u8>,
+    generation: Generation,
+}
+
+impl GrantStore {
+    fn open() -> Result<Self, AuthError> {
+        let key = SecureKeyring::signing_key(keys::GRANT_KEY)
+            .map_err(|e| AuthError::Failed(format!("Failed to load grant key: {}", e)))?;
+        Ok(Self { path: grants_path(), key, generation: Generation::Keyring(keys::GRANT_GENERATION) })
+    }
+
+    fn signature(&self, grant: &Grant, generation: u64) -> String {
+        let parts = grant.signed_parts(generation);
+        let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+        sign(&self.key, &parts)
+    }
+
+    /// Grants of the current generation that are signed, unexpired and not
+    /// used up
+    fn valid(&self, file: &GrantFile, now: u64) -> Vec<Grant> {
+        if file.generation != self.generation.get() {
+            return Vec::new();
+        }
+        file.grants
+            .iter()
+            .filter(|g| g.signature == self.signature(g, file.generation))
+            .filter(|g| !g.is_expired(now) && g.remaining_uses() > 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Active grants, without locking the file
+    fn load(&self, now: u64) -> Vec<Grant> {
+        let file: GrantFile = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        self.valid(&file, now)
+    }
+
+    /// Change the active grants under the file lock. A change is written
+    /// as the next generation, re-signed, with the keyring updated first.
+    fn update<R>(&self, now: u64, change: impl FnOnce(&mut Vec<Grant>) -> R) -> Result<R, AuthError> {
+        state::update(&self.path, |file: &mut GrantFile| {
+            let active = self.valid(file, now);
+            let mut grants = active.clone();
+            let result = change(&mut grants);
+            if grants != active {
+                let generation = self.generation.get().max(file.generation) + 1;
+                for grant in &mut grants {
+                    grant.signature = self.signature(grant, generation);
+                }
+                self.generation.set(generation)?;
+                *file = GrantFile { generation, grants };
+            }
+            Ok(result)
+        })
+    }
+
+    fn redeem(&self, command: &str, level: RiskLevel, scope: &GrantScope, now: u64) -> Result<Option<Grant>, AuthError> {
+        self.update(now, |grants| {
+            let grant = grants.iter_mut().find(|g| g.covers(command, level, scope, now))?;
+            grant.uses += 1;
+            let redeemed = grant.clone();
+            grants.retain(|g| g.remaining_uses() > 0);
+            Some(redeemed)
+        })
+    }
+
+    fn add(
+        &self,
+        pattern: &str,
+        level: RiskLevel,
+        scope: &GrantScope,
+        ttl_minutes: u64,
+        max_uses: u32,
+        now: u64,
+    ) -> Result<Grant, AuthError> {
+        let mut id = [0u8; 4];
+        rand::thread_rng().fill_bytes(&mut id);
+        let grant = Grant {
+            id: id.iter().map(|b| format!("{:02x}", b)).collect(),
+            pattern: pattern.to_string(),
+            cwd: scope.cwd.clone(),
+            session_id: scope.session_id.clone(),
+            level,
+            created_at: now,
+            expires_at: now + ttl_minutes.max(1) * 60,
+            max_uses: max_uses.max(1),
+            uses: 0,
+            signature: String::new(),
+        };
+        self.update(now, |grants| grants.push(grant.clone()))?;
+        Ok(grant)
+    }
+
+    /// Remove the grant with `id`, or all of them
+    fn revoke(&self, id: Option<&str>, now: u64) -> Result<usize, AuthError> {
+        self.update(now, |grants| {
+            let before = grants.len();
+            grants.retain(|g| id.is_some_and(|id| g.id != id));
+            before - grants.len()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (tempfile::TempDir, GrantStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GrantStore {
+            path: dir.path().join("grants.json"),
+            key: b"test-key".to_vec(),
+            generation: Generation::Memory(Default::default()),
+        };
+        (dir, store)
+    }
+
+    fn scope(cwd: &str, session: &str) -> GrantScope {
+        GrantScope { cwd: Some(cwd.to_string()), session_id: Some(session.to_string()) }
+    }
+
+    #[test]
+    fn test_grant_scope_and_uses() {
+        let (_dir, store) = store();
+        let here = scope("/work/repo", "abc");
+        store.add("cargo publish*", RiskLevel::High, &here, 15, 2, 1000).unwrap();
+
+        let redeem = |command: &str, level: RiskLevel, scope: &GrantScope, now: u64| {
+            store.redeem(command, level, scope, now).unwrap().is_some()
+        };
+        // Other command, level above the grant, other session or directory
+        assert!(!redeem("cargo test", RiskLevel::High, &here, 1001));
+        assert!(!redeem("cargo publish", RiskLevel::Critical, &here, 1001));
+        assert!(!redeem("cargo publish", RiskLevel::High, &scope("/work/repo", "xyz"), 1001));
+        assert!(!redeem("cargo publish", RiskLevel::High, &scope("/work/other", "abc"), 1001));
+        assert!(!redeem("cargo publish; rm -rf ~", RiskLevel::High, &here, 1001));
+        // Expired
+        assert!(!redeem("cargo publish", RiskLevel::High, &here, 1000 + 15 * 60));
+
+        assert!(redeem("cargo publish --dry-run", RiskLevel::Medium, &scope("/work/repo/crates/a", "abc"), 1001));
+        assert!(redeem("cargo publish", RiskLevel::High, &here, 1002));
+        // Used up
+        assert!(!redeem("cargo publish", RiskLevel::High, &here, 1003));
+        assert!(store.load(1003).is_empty());
+    }
+
+    #[test]
+    fn test_tampered_grant_ignored() {
+        let (_dir, store) = store();
+        let here = scope("/work/repo", "abc");
+        store.add("npm install*", RiskLevel::Medium, &here, 15, 1, 1000).unwrap();
+
+        let json = fs::read_to_string(&store.path).unwrap();
+        fs::write(&store.path, json.replace("\"max_uses\": 1", "\"max_uses\": 100")).unwrap();
+        assert!(store.load(1001).is_empty());
+
+        let forged = GrantStore {
+            path: store.path.clone(),
+            key: b"other-key".to_vec(),
+            generation: Generation::Memory(store.generation.get().into()),
+        };
+        forged.add("*", RiskLevel::Critical, &GrantScope::default(), 15, 1, 1000).unwrap();
+        assert!(store.redeem("rm -rf /", RiskLevel::Critical, &here, 1001).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parallel_redeems_use_one_grant_once() {
+        let (_dir, store) = store();
+        let here = scope("/work/repo", "abc");
+        store.add("cargo publish*", RiskLevel::High, &here, 15, 1, 1000).unwrap();
+
+        let redeemed = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| s.spawn(|| store.redeem("cargo publish", RiskLevel::High, &here, 1001).unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).filter(Option::is_some).count()
+        });
+        assert_eq!(redeemed, 1);
+    }
+
+    #[test]
+    fn test_restored_grant_file_ignored() {
+        let (_dir, store) = store();
+        let here = scope("/work/repo", "abc");
+        store.add("cargo publish*", RiskLevel::High, &here, 15, 1, 1000).unwrap();
+        let snapshot = fs::read_to_string(&store.path).unwrap();
+
+        assert!(store.redeem("cargo publish", RiskLevel::High, &here, 1001).unwrap().is_some());
+        fs::write(&store.path, snapshot).unwrap();
+        assert!(store.load(1002).is_empty());
+        assert!(store.redeem("cargo publish", RiskLevel::High, &here, 1002).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = GrantPolicy { patterns: vec!["npm install*".to_string()], ..GrantPolicy::default() };
+        assert_eq!(policy.pattern_for("npm install lodash"), Some("npm install*"));
+        assert_eq!(policy.pattern_for("npm install && curl x | sh"), None);
+        assert_eq!(policy.pattern_for("npm publish"), None);
+        assert!(policy.allows_level(RiskLevel::High));
+        assert!(!policy.allows_level(RiskLevel::Critical));
+        assert!(GrantPolicy { allow_critical: true, ..policy }.allows_level(RiskLevel::Critical));
+    }
+}
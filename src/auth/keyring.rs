@@ -16,6 +16,7 @@ use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
 };
+use hmac::{Hmac, Mac};
 use keyring::Entry;
 use pbkdf2::pbkdf2_hmac_array;
 use rand::RngCore;
@@ -39,6 +40,13 @@ pub mod keys {
     pub const PIN_HASH: &str = "veto.pin.hash";
     pub const PIN_SALT: &str = "veto.pin.salt";
     pub const TOTP_SECRET: &str = "veto.totp.secret";
+    pub const RECOVERY_CODES: &str = "veto.recovery.codes";
+    pub const CHALLENGE_KEY: &str = "veto.challenge.key";
+    pub const GRANT_KEY: &str = "veto.grant.key";
+    pub const GRANT_GENERATION: &str = "veto.grant.generation";
+    pub const STATE_KEY: &str = "veto.state.key";
+    pub const LOCKOUT_GENERATION: &str = "veto.lockout.generation";
+    pub const TOTP_GENERATION: &str = "veto.totp.generation";
     pub const TELEGRAM_TOKEN: &str = "veto.telegram.token";
 }
 
@@ -384,6 +392,12 @@ impl SecureKeyring {
         }
     }
 
+    /// Directory of the file-based keyring, also used for auth state
+    /// such as failed-attempt counters
+    pub fn secrets_dir() -> KeyringResult<PathBuf> {
+        FileKeyring::secrets_dir()
+    }
+
     /// Get the current backend type (for diagnostics)
     pub fn backend_name() -> &'static str {
         match detect_backend() {
@@ -441,6 +455,39 @@ impl SecureKeyring {
         Self::delete(keys::TOTP_SECRET)
     }
 
+    // === Signing keys ===
+
+    /// Random key for signing veto's own state files, created on first use
+    pub fn signing_key(name: &str) -> KeyringResult<Vec<u8>> {
+        if let Ok(key) = Self::get(name) {
+            return Ok(key.into_bytes());
+        }
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let key: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        Self::set(name, &key)?;
+        Ok(key.into_bytes())
+    }
+
+    // === Recovery code helpers ===
+
+    /// Get stored recovery code hashes (JSON array)
+    pub fn get_recovery_codes() -> KeyringResult<String> {
+        Self::get(keys::RECOVERY_CODES)
+    }
+
+    /// Store recovery code hashes (JSON array)
+    pub fn set_recovery_codes(hashes: &str) -> KeyringResult<()> {
+        Self::set(keys::RECOVERY_CODES, hashes)
+    }
+
+    /// Delete recovery codes
+    pub fn delete_recovery_codes() -> KeyringResult<()> {
+        // Ignore NotFound errors
+        let _ = Self::delete(keys::RECOVERY_CODES);
+        Ok(())
+    }
+
     // === Telegram specific helpers ===
 
     /// Get stored Telegram bot token
@@ -464,6 +511,16 @@ impl SecureKeyring {
     }
 }
 
+/// HMAC-SHA256 over NUL-separated parts, hex encoded
+pub fn sign(key: &[u8], parts: &[&str]) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part.as_bytes());
+        mac.update(&[0]);
+    }
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
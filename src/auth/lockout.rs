@@ -0,0 +1,302 @@
+//! Failed-attempt limiting for PIN, TOTP and recovery codes
+//!
+//! Every failure doubles the wait before the next attempt, and after
+//! `max_attempts` failures in a row the method stays locked until
+//! `veto auth unlock`. Counters live next to the file keyring in
+//! `~/.veto/secrets/lockout.json`, so they survive across hook calls. The
+//! file is sealed (see [`state`]): when it was deleted, edited or restored
+//! from an older copy, every limited method counts as locked.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::challenge::{html_escape, notify};
+use super::keyring::{keys, SecureKeyring};
+use super::state::{self, Seal};
+use super::AuthError;
+use crate::config::loader::load_config;
+
+/// Methods with attempt limits
+pub const LIMITED_METHODS: &[&str] = &["pin", "totp", "recovery"];
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_BACKOFF_SECONDS: u64 = 1;
+const MAX_BACKOFF_SECONDS: u64 = 15 * 60;
+
+/// Failed attempts for one method
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AttemptState {
+    /// Consecutive failures
+    pub failures: u32,
+    /// Unix timestamp of the last failure
+    pub last_failure: u64,
+    /// Locked until `veto auth unlock`
+    pub locked: bool,
+}
+
+/// Limits from `[auth.lockout]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    pub max_attempts: u32,
+    pub backoff_seconds: u64,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self { max_attempts: DEFAULT_MAX_ATTEMPTS, backoff_seconds: DEFAULT_BACKOFF_SECONDS }
+    }
+}
+
+impl LockoutPolicy {
+    /// Policy from the user's config
+    pub fn load() -> Self {
+        let lockout = load_config().ok().and_then(|c| c.auth).and_then(|a| a.lockout);
+        let defaults = Self::default();
+        Self {
+            max_attempts: lockout.as_ref().and_then(|l| l.max_attempts).unwrap_or(defaults.max_attempts).max(1),
+            backoff_seconds: lockout.and_then(|l| l.backoff_seconds).unwrap_or(defaults.backoff_seconds),
+        }
+    }
+
+    /// Seconds to wait after `failures` consecutive failures
+    pub fn backoff(&self, failures: u32) -> u64 {
+        if failures == 0 {
+            return 0;
+        }
+        let factor = 1u64 << (failures - 1).min(32);
+        self.backoff_seconds.saturating_mul(factor).min(MAX_BACKOFF_SECONDS)
+    }
+}
+
+impl AttemptState {
+    /// Seconds left before another attempt is allowed
+    pub fn wait(&self, policy: &LockoutPolicy, now: u64) -> u64 {
+        (self.last_failure + policy.backoff(self.failures)).saturating_sub(now)
+    }
+
+    /// Count a failure; returns true when it locks the method
+    fn fail(&mut self, policy: &LockoutPolicy, now: u64) -> bool {
+        self.failures += 1;
+        self.last_failure = now;
+        if !self.locked && self.failures >= policy.max_attempts {
+            self.locked = true;
+            return true;
+        }
+        false
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn label(method: &str) -> String {
+    method.to_uppercase()
+}
+
+fn state_path() -> Result<PathBuf, AuthError> {
+    SecureKeyring::secrets_dir()
+        .map(|dir| dir.join("lockout.json"))
+        .map_err(|e| AuthError::Failed(e.to_string()))
+}
+
+fn seal() -> Result<Seal, AuthError> {
+    Seal::open("lockout", keys::LOCKOUT_GENERATION)
+}
+
+/// Counters assumed when the file cannot be trusted: every limited method
+/// locked
+fn tampered(policy: &LockoutPolicy, now: u64) -> HashMap<String, AttemptState> {
+    LIMITED_METHODS
+        .iter()
+        .map(|m| (m.to_string(), AttemptState { failures: policy.max_attempts, last_failure: now, locked: true }))
+        .collect()
+}
+
+fn load_states() -> HashMap<String, AttemptState> {
+    let states = state_path().and_then(|path| Ok(state::load_sealed(&path, &seal()?)));
+    match states {
+        Ok(Some(states)) => states,
+        _ => tampered(&LockoutPolicy::load(), now()),
+    }
+}
+
+/// Failed attempts recorded for a method
+pub fn state(method: &str) -> AttemptState {
+    load_states().remove(method).unwrap_or_default()
+}
+
+/// Whether a method is locked until `veto auth unlock`
+pub fn is_locked(method: &str) -> bool {
+    state(method).locked
+}
+
+/// Check a code under the attempt limit for `method`
+///
+/// Refuses without calling `verify` while locked or backing off. A
+/// success clears the counter; the failure that reaches the limit locks
+/// the method and alerts the user. The counters stay locked from the check
+/// until the result is recorded, so parallel attempts are counted one by
+/// one.
+pub fn guard(method: &str, verify: impl FnOnce() -> Result<bool, AuthError>) -> Result<bool, AuthError> {
+    guard_at(&state_path()?, &seal()?, &LockoutPolicy::load(), method, now(), verify)
+}
+
+fn guard_at(
+    path: &Path,
+    seal: &Seal,
+    policy: &LockoutPolicy,
+    method: &str,
+    now: u64,
+    verify: impl FnOnce() -> Result<bool, AuthError>,
+) -> Result<bool, AuthError> {
+    let mut locked_after = None;
+    let verified = state::update_sealed(path, seal, |states: &mut HashMap<String, AttemptState>, intact| {
+        if !intact {
+            return Err(AuthError::Locked(format!(
+                "{} locked: the attempt counters were deleted or altered. Run 'veto auth unlock' in a terminal.",
+                label(method)
+            )));
+        }
+        let mut state = states.get(method).cloned().unwrap_or_default();
+        if state.locked {
+            return Err(AuthError::Locked(format!(
+                "{} locked after {} failed attempts. Run 'veto auth unlock' in a terminal.",
+                label(method),
+                state.failures
+            )));
+        }
+        let wait = state.wait(policy, now);
+        if wait > 0 {
+            return Err(AuthError::Locked(format!(
+                "Too many failed {} attempts. Try again in {}s.",
+                label(method),
+                wait
+            )));
+        }
+
+        let verified = verify()?;
+        if verified {
+            states.remove(method);
+        } else {
+            if state.fail(policy, now) {
+                locked_after = Some(state.failures);
+            }
+            states.insert(method.to_string(), state);
+        }
+        Ok(verified)
+    })?;
+
+    if let Some(failures) = locked_after {
+        alert(method, failures);
+    }
+    Ok(verified)
+}
+
+/// Clear the counter for a method
+///
+/// Counters that cannot be trusted are re-established with every other
+/// limited method still locked.
+pub fn reset(method: &str) -> Result<(), AuthError> {
+    reset_at(&state_path()?, &seal()?, &LockoutPolicy::load(), method, now())
+}
+
+fn reset_at(path: &Path, seal: &Seal, policy: &LockoutPolicy, method: &str, now: u64) -> Result<(), AuthError> {
+    state::update_sealed(path, seal, |states: &mut HashMap<String, AttemptState>, intact| {
+        if !intact {
+            *states = tampered(policy, now);
+        }
+        states.remove(method);
+        Ok(())
+    })
+}
+
+/// Tell the user a method was locked, out of the agent's sight
+fn alert(method: &str, failures: u32) {
+    let body = format!("{} locked after {} failed attempts", label(method), failures);
+    let telegram = format!(
+        "🚫 <b>Veto lockout</b>\n\n{}.\n\nRun <code>veto auth unlock</code> in a terminal to unlock.",
+        html_escape(&body)
+    );
+    let _ = notify("veto Lockout", &body, &telegram);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_backoff_and_lockout() {
+        let policy = LockoutPolicy { max_attempts: 3, backoff_seconds: 2 };
+        assert_eq!(policy.backoff(0), 0);
+        assert_eq!(policy.backoff(1), 2);
+        assert_eq!(policy.backoff(3), 8);
+        assert_eq!(policy.backoff(40), MAX_BACKOFF_SECONDS);
+
+        let mut state = AttemptState::default();
+        assert!(!state.fail(&policy, 100));
+        assert_eq!(state.wait(&policy, 100), 2);
+        assert_eq!(state.wait(&policy, 102), 0);
+        assert!(!state.fail(&policy, 102));
+        assert!(state.fail(&policy, 106));
+        assert!(state.locked);
+        // Further failures do not alert again
+        assert!(!state.fail(&policy, 200));
+    }
+
+    #[test]
+    fn test_parallel_failures_all_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lockout.json");
+        let seal = Seal::memory("lockout");
+        let policy = LockoutPolicy { max_attempts: 100, backoff_seconds: 0 };
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    guard_at(&path, &seal, &policy, "pin", 100, || {
+                        std::thread::sleep(std::time::Duration::from_millis(20));
+                        Ok(false)
+                    })
+                });
+            }
+        });
+        let states: HashMap<String, AttemptState> = state::load_sealed(&path, &seal).unwrap();
+        assert_eq!(states["pin"].failures, 8);
+    }
+
+    #[test]
+    fn test_untrusted_counters_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lockout.json");
+        let seal = Seal::memory("lockout");
+        let policy = LockoutPolicy { max_attempts: 5, backoff_seconds: 0 };
+        let attempt = |verified: bool| guard_at(&path, &seal, &policy, "pin", 100, || Ok(verified));
+
+        assert!(attempt(true).unwrap());
+        assert!(!attempt(false).unwrap());
+        let snapshot = fs::read_to_string(&path).unwrap();
+        assert!(snapshot.contains("\"failures\": 1"));
+        assert!(!attempt(false).unwrap());
+
+        // Deleted, rolled back or edited
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(attempt(true), Err(AuthError::Locked(_))));
+        fs::write(&path, &snapshot).unwrap();
+        assert!(matches!(attempt(true), Err(AuthError::Locked(_))));
+        fs::write(&path, snapshot.replace("\"failures\": 1", "\"failures\": 0")).unwrap();
+        assert!(matches!(attempt(true), Err(AuthError::Locked(_))));
+        assert!(state::load_sealed::<HashMap<String, AttemptState>>(&path, &seal).is_none());
+
+        // Unlocking one method re-establishes the file with the others locked
+        reset_at(&path, &seal, &policy, "pin", 200).unwrap();
+        let states: HashMap<String, AttemptState> = state::load_sealed(&path, &seal).unwrap();
+        assert!(!states.contains_key("pin"));
+        assert!(states["totp"].locked && states["recovery"].locked);
+        assert!(attempt(true).unwrap());
+    }
+}
@@ -37,6 +37,44 @@ impl<T: AsyncAuthenticator + 'static> Authenticator for AsyncAuthBridge<T> {
     }
 }
 
+/// One step of an auth chain, satisfied by any of its methods
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthFactor {
+    pub methods: Vec<String>,
+}
+
+impl AuthFactor {
+    pub fn single(method: impl Into<String>) -> Self {
+        Self { methods: vec![method.into()] }
+    }
+
+    pub fn any_of(methods: Vec<String>) -> Self {
+        Self { methods }
+    }
+}
+
+impl std::fmt::Display for AuthFactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.methods.join("|"))
+    }
+}
+
+impl PartialEq<&str> for AuthFactor {
+    fn eq(&self, other: &&str) -> bool {
+        self.methods.len() == 1 && self.methods[0] == *other
+    }
+}
+
+/// Flatten a configured method into chain steps
+fn factors(method: &AuthMethod) -> Vec<AuthFactor> {
+    match method {
+        AuthMethod::Single(m) => vec![AuthFactor::single(m.clone())],
+        AuthMethod::Multiple(steps) => steps.iter().flat_map(factors).collect(),
+        AuthMethod::AnyOf { any_of } if any_of.is_empty() => vec![],
+        AuthMethod::AnyOf { any_of } => vec![AuthFactor::any_of(any_of.clone())],
+    }
+}
+
 /// Authentication manager - selects and executes authentication methods
 pub struct AuthManager {
     config: AuthConfig,
@@ -47,8 +85,8 @@ impl AuthManager {
         Self { config }
     }
 
-    /// Get authentication method for a risk level
-    pub fn get_methods_for_level(&self, level: &RiskLevel) -> Vec<String> {
+    /// Get the auth chain for a risk level: every factor is required
+    pub fn get_methods_for_level(&self, level: &RiskLevel) -> Vec<AuthFactor> {
         let level_key = match level {
             RiskLevel::Allow => return vec![],  // No auth needed
             RiskLevel::Low => "low",
@@ -60,21 +98,54 @@ impl AuthManager {
         // Check levels config first
         if let Some(levels) = &self.config.levels {
             if let Some(method) = levels.get(level_key) {
-                return match method {
-                    AuthMethod::Single(m) => vec![m.clone()],
-                    // For backwards compatibility, array configs use first element only
-                    AuthMethod::Multiple(ms) => ms.first().cloned().map(|m| vec![m]).unwrap_or_default(),
-                };
+                let chain = factors(method);
+                if !chain.is_empty() {
+                    return chain;
+                }
             }
         }
 
         // Fall back to default
         if let Some(default) = &self.config.default {
-            vec![default.clone()]
+            vec![AuthFactor::single(default.clone())]
         } else {
-            vec![crate::auth::default_auth_method().to_string()]
+            vec![AuthFactor::single(crate::auth::default_auth_method())]
         }
     }
+
+    /// A method followed by its configured fallbacks (`telegram -> totp -> pin`)
+    ///
+    /// Fallbacks weaker than `fallback_floor` (default `pin`) are skipped,
+    /// though the floor never asks for more than the method being replaced.
+    pub fn fallback_chain(&self, method: &str) -> Vec<String> {
+        let floor_method = self.config.fallback_floor.as_deref().unwrap_or("pin");
+        let floor = method_strength(floor_method).min(method_strength(method));
+
+        let mut chain = vec![method.to_string()];
+        let mut visited = vec![method];
+        let mut current = method;
+        while let Some(next) = self.config.fallback.as_ref().and_then(|f| f.get(current)) {
+            if visited.contains(&next.as_str()) {
+                break;
+            }
+            visited.push(next);
+            if method_strength(next) >= floor {
+                chain.push(next.clone());
+            }
+            current = next;
+        }
+        chain
+    }
+}
+
+/// Relative strength of an auth method, for the fallback floor
+pub fn method_strength(method: &str) -> u8 {
+    match method {
+        "dialog" => 1,
+        "pin" => 2,
+        "totp" | "recovery" | "touchid" | "telegram" => 3,
+        _ => 0,
+    }
 }
 
 /// Factory for creating authenticators by name
@@ -87,6 +158,7 @@ impl AuthenticatorFactory {
             "confirm" => true,
             "pin" => super::keyring::SecureKeyring::has_pin(),
             "totp" => super::keyring::SecureKeyring::has_totp(),
+            "recovery" => super::RecoveryAuth::remaining() > 0,
             "touchid" => cfg!(target_os = "macos"),
             "telegram" => {
                 super::keyring::SecureKeyring::has_telegram()
@@ -101,6 +173,7 @@ impl AuthenticatorFactory {
             ("confirm", true),
             ("pin", super::keyring::SecureKeyring::has_pin()),
             ("totp", super::keyring::SecureKeyring::has_totp()),
+            ("recovery", super::RecoveryAuth::remaining() > 0),
             ("touchid", cfg!(target_os = "macos")),
             ("telegram", super::keyring::SecureKeyring::has_telegram()),
         ]
@@ -117,9 +190,14 @@ mod tests {
         let mut levels = HashMap::new();
         levels.insert("low".to_string(), AuthMethod::Single("confirm".to_string()));
         levels.insert("high".to_string(), AuthMethod::Multiple(vec![
-            "pin".to_string(),
-            "totp".to_string(),
+            AuthMethod::Single("pin".to_string()),
+            AuthMethod::Single("totp".to_string()),
         ]));
+        levels.insert("critical".to_string(), AuthMethod::Multiple(vec![
+            AuthMethod::Single("pin".to_string()),
+            AuthMethod::AnyOf { any_of: vec!["touchid".to_string(), "telegram".to_string()] },
+        ]));
+        levels.insert("medium".to_string(), AuthMethod::AnyOf { any_of: vec![] });
 
         let config = AuthConfig {
             default: Some("confirm".to_string()),
@@ -129,11 +207,39 @@ mod tests {
 
         let manager = AuthManager::new(config);
 
-        assert_eq!(manager.get_methods_for_level(&RiskLevel::Allow), Vec::<String>::new());
+        assert_eq!(manager.get_methods_for_level(&RiskLevel::Allow), Vec::<AuthFactor>::new());
         assert_eq!(manager.get_methods_for_level(&RiskLevel::Low), vec!["confirm"]);
-        // For backwards compatibility, array configs use first element only
-        assert_eq!(manager.get_methods_for_level(&RiskLevel::High), vec!["pin"]);
-        // Medium not configured, should fall back to default
+        assert_eq!(manager.get_methods_for_level(&RiskLevel::High), vec!["pin", "totp"]);
+
+        let critical = manager.get_methods_for_level(&RiskLevel::Critical);
+        assert_eq!(critical.len(), 2);
+        assert_eq!(critical[1].methods, vec!["touchid", "telegram"]);
+        assert_eq!(critical[1].to_string(), "touchid|telegram");
+
+        // An empty any_of falls back to default
         assert_eq!(manager.get_methods_for_level(&RiskLevel::Medium), vec!["confirm"]);
     }
+
+    #[test]
+    fn test_fallback_chain() {
+        let mut fallback = HashMap::new();
+        fallback.insert("telegram".to_string(), "confirm".to_string());
+        fallback.insert("confirm".to_string(), "totp".to_string());
+        fallback.insert("totp".to_string(), "pin".to_string());
+        fallback.insert("pin".to_string(), "telegram".to_string());
+
+        let manager = AuthManager::new(AuthConfig { fallback: Some(fallback.clone()), ..Default::default() });
+        // confirm is below the default floor; the cycle back to telegram stops the walk
+        assert_eq!(manager.fallback_chain("telegram"), vec!["telegram", "totp", "pin"]);
+        // The floor never exceeds the method being replaced
+        assert_eq!(manager.fallback_chain("confirm"), vec!["confirm", "totp", "pin", "telegram"]);
+        assert_eq!(manager.fallback_chain("dialog"), vec!["dialog"]);
+
+        let manager = AuthManager::new(AuthConfig {
+            fallback: Some(fallback),
+            fallback_floor: Some("totp".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(manager.fallback_chain("telegram"), vec!["telegram", "totp"]);
+    }
 }
@@ -4,6 +4,7 @@
 //! - confirm: Simple y/n confirmation
 //! - pin: PIN code with Argon2 hashing
 //! - totp: Time-based OTP (Google Authenticator compatible)
+//! - recovery: One-time recovery codes, accepted wherever TOTP is
 //! - touchid: macOS Touch ID (platform-specific)
 //! - telegram: Async Telegram bot approval
 //! - challenge: Challenge-response for preventing AI replay attacks
@@ -11,21 +12,26 @@
 mod confirm;
 mod pin;
 mod totp;
+mod recovery;
 mod touchid;
 mod telegram;
 mod dialog;
 pub mod challenge;
+pub mod grants;
 pub mod keyring;
+pub mod lockout;
+pub(crate) mod state;
 pub mod manager;
 
 pub use confirm::*;
 pub use pin::*;
 pub use totp::*;
+pub use recovery::*;
 pub use touchid::*;
 pub use telegram::*;
 pub use dialog::*;
-pub use manager::{AuthManager, AuthenticatorFactory};
-pub use challenge::{Challenge, notify_challenge, verify_response};
+pub use manager::{AuthFactor, AuthManager, AuthenticatorFactory};
+pub use challenge::{Challenge, notify_challenge, takes_response, verify_response};
 
 use thiserror::Error;
 
@@ -39,6 +45,9 @@ pub enum AuthError {
     NotAvailable(String),
     #[error("Authentication timeout")]
     Timeout,
+    /// Too many failed attempts (backoff or lockout)
+    #[error("{0}")]
+    Locked(String),
 }
 
 pub type AuthResult = Result<bool, AuthError>;
@@ -54,6 +63,9 @@ pub struct AuthContext {
     pub tool_name: Option<String>,
     /// File path being operated on (for file operations)
     pub file_path: Option<String>,
+    /// Challenge code shown in the prompt, to cross-check against the
+    /// notification
+    pub challenge: Option<String>,
 }
 
 impl AuthContext {
@@ -81,6 +93,11 @@ impl AuthContext {
         self
     }
 
+    pub fn with_challenge(mut self, challenge: impl Into<String>) -> Self {
+        self.challenge = Some(challenge.into());
+        self
+    }
+
     /// Format context for display in dialog/touchid prompts
     pub fn format_for_display(&self) -> String {
         let mut parts = Vec::new();
@@ -117,6 +134,10 @@ impl AuthContext {
             parts.push(format!("Session: {}...", short_id));
         }
 
+        if let Some(ref challenge) = self.challenge {
+            parts.push(format!("Challenge: {} (check it matches the notification)", challenge));
+        }
+
         parts.join("\\n")
     }
 }
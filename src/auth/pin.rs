@@ -11,6 +11,7 @@ use colored::Colorize;
 use dialoguer::Password;
 
 use super::keyring::SecureKeyring;
+use super::lockout;
 use super::{AuthError, AuthResult, Authenticator};
 
 /// PIN authenticator using Argon2 hash verification
@@ -53,7 +54,7 @@ impl PinAuth {
         self.verify_pin(pin)
     }
 
-    /// Verify a PIN against the stored hash
+    /// Verify a PIN against the stored hash, under the attempt limit
     fn verify_pin(&self, pin: &str) -> Result<bool, AuthError> {
         let stored_hash = SecureKeyring::get_pin_hash()
             .map_err(|_| AuthError::NotAvailable("PIN not configured".to_string()))?;
@@ -61,10 +62,9 @@ impl PinAuth {
         let parsed_hash = PasswordHash::new(&stored_hash)
             .map_err(|e| AuthError::Failed(format!("Invalid stored hash: {}", e)))?;
 
-        match Argon2::default().verify_password(pin.as_bytes(), &parsed_hash) {
-            Ok(()) => Ok(true),
-            Err(_) => Ok(false),
-        }
+        lockout::guard("pin", || {
+            Ok(Argon2::default().verify_password(pin.as_bytes(), &parsed_hash).is_ok())
+        })
     }
 }
 
@@ -0,0 +1,163 @@
+//! One-time recovery codes
+//!
+//! Generated with TOTP and accepted anywhere TOTP is, so a lost
+//! authenticator or a locked PIN/TOTP is not a dead end. Codes are stored
+//! as Argon2 hashes in the keyring, like the PIN, and each one is removed
+//! once used.
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use colored::Colorize;
+use dialoguer::Password;
+use rand::Rng;
+
+use super::keyring::SecureKeyring;
+use super::lockout;
+use super::{AuthError, AuthResult, Authenticator};
+
+/// Codes generated per set
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Characters in a code (no 0/o, 1/l/i)
+const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Characters per half of a code (`xxxxx-xxxxx`)
+const HALF_LENGTH: usize = 5;
+
+/// Recovery code authenticator
+pub struct RecoveryAuth;
+
+impl RecoveryAuth {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Replace any existing codes with a new set; returns them in plain text
+    /// for the user to write down
+    pub fn generate() -> Result<Vec<String>, AuthError> {
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| new_code()).collect();
+        let hashes = codes.iter().map(|code| hash_code(code)).collect::<Result<Vec<_>, _>>()?;
+        save_hashes(&hashes)?;
+        let _ = lockout::reset("recovery");
+        Ok(codes)
+    }
+
+    /// Number of unused codes
+    pub fn remaining() -> usize {
+        load_hashes().len()
+    }
+
+    /// Delete all codes
+    pub fn delete() -> Result<(), AuthError> {
+        let _ = lockout::reset("recovery");
+        SecureKeyring::delete_recovery_codes()
+            .map_err(|e| AuthError::Failed(format!("Failed to delete recovery codes: {}", e)))
+    }
+
+    /// Verify a recovery code under the attempt limit, using it up on success
+    pub fn verify(code: &str) -> Result<bool, AuthError> {
+        if load_hashes().is_empty() {
+            return Err(AuthError::NotAvailable("No recovery codes left".to_string()));
+        }
+        let code = normalize(code);
+
+        // Read the codes under the attempt lock, so two parallel calls
+        // cannot both use the same one
+        lockout::guard("recovery", || {
+            let mut hashes = load_hashes();
+            let used = hashes.iter().position(|hash| {
+                PasswordHash::new(hash).is_ok_and(|parsed| Argon2::default().verify_password(code.as_bytes(), &parsed).is_ok())
+            });
+            let Some(index) = used else {
+                return Ok(false);
+            };
+            hashes.remove(index);
+            save_hashes(&hashes)?;
+            Ok(true)
+        })
+    }
+}
+
+impl Default for RecoveryAuth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Authenticator for RecoveryAuth {
+    fn is_available(&self) -> bool {
+        Self::remaining() > 0
+    }
+
+    fn authenticate(&self, command: &str) -> AuthResult {
+        eprintln!("{} {}", "Command:".yellow(), command);
+        eprintln!("{}", "Recovery code required for this operation".red());
+
+        let entered = Password::new()
+            .with_prompt("Enter recovery code")
+            .interact()
+            .map_err(|_| AuthError::Cancelled)?;
+
+        if Self::verify(&entered)? {
+            eprintln!("{} ({} left)", "✓ Recovery code accepted".green(), Self::remaining());
+            Ok(true)
+        } else {
+            Err(AuthError::Failed("Invalid recovery code".to_string()))
+        }
+    }
+}
+
+fn new_code() -> String {
+    let mut rng = rand::thread_rng();
+    let mut half = || -> String {
+        (0..HALF_LENGTH).map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char).collect()
+    };
+    format!("{}-{}", half(), half())
+}
+
+/// Codes are compared without case, dashes or spaces
+fn normalize(code: &str) -> String {
+    code.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+fn hash_code(code: &str) -> Result<String, AuthError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(normalize(code).as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AuthError::Failed(format!("Failed to hash recovery code: {}", e)))
+}
+
+fn load_hashes() -> Vec<String> {
+    SecureKeyring::get_recovery_codes()
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_hashes(hashes: &[String]) -> Result<(), AuthError> {
+    let json = serde_json::to_string(hashes)
+        .map_err(|e| AuthError::Failed(format!("Failed to serialize recovery codes: {}", e)))?;
+    SecureKeyring::set_recovery_codes(&json)
+        .map_err(|e| AuthError::Failed(format!("Failed to store recovery codes: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_format_and_hash() {
+        let code = new_code();
+        assert_eq!(code.len(), HALF_LENGTH * 2 + 1);
+        assert!(code.chars().all(|c| c == '-' || ALPHABET.contains(&(c as u8))));
+
+        let hash = hash_code(&code).unwrap();
+        let parsed = PasswordHash::new(&hash).unwrap();
+        let typed = normalize(&format!(" {} ", code.to_uppercase().replace('-', "")));
+        assert!(Argon2::default().verify_password(typed.as_bytes(), &parsed).is_ok());
+        assert!(Argon2::default().verify_password(b"aaaaaaaaaa", &parsed).is_err());
+    }
+}
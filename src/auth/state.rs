@@ -0,0 +1,220 @@
+//! Locked read-modify-write of JSON state files
+//!
+//! Attempt counters, used TOTP steps, grants and the deny cache are
+//! checked and updated by concurrent hook calls. Each update holds an
+//! exclusive lock on a `<file>.lock` next to the state from read to write,
+//! so parallel calls cannot both start from the same state.
+//!
+//! Files an agent must not reset (attempt counters, used TOTP steps) are
+//! also sealed: a MAC over the content and a generation that is bumped on
+//! every write and mirrored in the keyring. A file that was edited,
+//! deleted or restored from an older copy no longer matches, and callers
+//! fail closed on it.
+
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::keyring::{keys, sign, SecureKeyring};
+use super::AuthError;
+
+/// Read the state at `path` (default when missing or unreadable), let
+/// `update` change it, and write it back if it succeeded and changed the
+/// state, all under the lock
+pub fn update<T, R>(path: &Path, update: impl FnOnce(&mut T) -> Result<R, AuthError>) -> Result<R, AuthError>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let _lock = lock(path)?;
+    let original = fs::read_to_string(path).ok();
+    let mut state: T = original.as_deref().and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default();
+
+    let result = update(&mut state)?;
+
+    let json = serde_json::to_string_pretty(&state)
+        .map_err(|e| AuthError::Failed(format!("Failed to serialize {}: {}", name(path), e)))?;
+    if original.as_deref() != Some(json.as_str()) {
+        write(path, &json)?;
+    }
+    Ok(result)
+}
+
+/// Where the generation of a sealed file is kept
+pub enum Generation {
+    /// Keyring entry holding the number
+    Keyring(&'static str),
+    #[cfg(test)]
+    Memory(std::sync::Mutex<u64>),
+}
+
+impl Generation {
+    /// The last generation written; 0 before the first write
+    pub fn get(&self) -> u64 {
+        match self {
+            Generation::Keyring(name) => {
+                SecureKeyring::get(name).ok().and_then(|value| value.parse().ok()).unwrap_or(0)
+            }
+            #[cfg(test)]
+            Generation::Memory(value) => *value.lock().unwrap(),
+        }
+    }
+
+    pub fn set(&self, generation: u64) -> Result<(), AuthError> {
+        match self {
+            Generation::Keyring(name) => SecureKeyring::set(name, &generation.to_string())
+                .map_err(|e| AuthError::Failed(format!("Failed to store {}: {}", name, e))),
+            #[cfg(test)]
+            Generation::Memory(value) => {
+                *value.lock().unwrap() = generation;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Key and generation a state file is sealed with
+pub struct Seal {
+    /// Kind of state, so one file cannot stand in for another
+    label: &'static str,
+    key: Vec<u8>,
+    generation: Generation,
+}
+
+impl Seal {
+    /// Seal under the keyring's state key, with the generation kept in
+    /// the keyring entry `generation`
+    pub fn open(label: &'static str, generation: &'static str) -> Result<Self, AuthError> {
+        let key = SecureKeyring::signing_key(keys::STATE_KEY)
+            .map_err(|e| AuthError::Failed(format!("Failed to load state key: {}", e)))?;
+        Ok(Self { label, key, generation: Generation::Keyring(generation) })
+    }
+
+    #[cfg(test)]
+    pub fn memory(label: &'static str) -> Self {
+        Self { label, key: b"test-key".to_vec(), generation: Generation::Memory(Default::default()) }
+    }
+
+    fn mac(&self, generation: u64, content: &str) -> String {
+        sign(&self.key, &[self.label, &generation.to_string(), content])
+    }
+
+    /// Whether `file` is the state last written under this seal; no file
+    /// at all only counts before the first write
+    fn verifies<T: Serialize>(&self, file: Option<&Sealed<T>>) -> bool {
+        let current = self.generation.get();
+        match file {
+            None => current == 0,
+            Some(file) => {
+                file.generation == current
+                    && canonical(&file.state).is_some_and(|content| file.mac == self.mac(current, &content))
+            }
+        }
+    }
+}
+
+/// A state with its generation and MAC
+#[derive(Serialize, Deserialize)]
+struct Sealed<T> {
+    generation: u64,
+    state: T,
+    mac: String,
+}
+
+/// JSON with sorted keys, so maps serialize the same on every run
+fn canonical<T: Serialize>(state: &T) -> Option<String> {
+    serde_json::to_value(state).ok().map(|value| value.to_string())
+}
+
+/// The sealed state at `path`, or `None` when it is not the one last
+/// written
+pub fn load_sealed<T>(path: &Path, seal: &Seal) -> Option<T>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let file: Option<Sealed<T>> = fs::read_to_string(path).ok().and_then(|json| serde_json::from_str(&json).ok());
+    if !seal.verifies(file.as_ref()) {
+        return None;
+    }
+    Some(file.map(|file| file.state).unwrap_or_default())
+}
+
+/// Like [`update`] for a sealed file. `change` gets the state and whether
+/// it verified; one that did not starts from the default. A successful
+/// change is written as the next generation, with the keyring updated
+/// first; a file that did not verify is rewritten even when unchanged.
+pub fn update_sealed<T, R>(
+    path: &Path,
+    seal: &Seal,
+    change: impl FnOnce(&mut T, bool) -> Result<R, AuthError>,
+) -> Result<R, AuthError>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    update(path, |file: &mut Option<Sealed<T>>| {
+        let intact = seal.verifies(file.as_ref());
+        let last = seal.generation.get().max(file.as_ref().map_or(0, |file| file.generation));
+        let mut state = match file.take() {
+            Some(file) if intact => file.state,
+            _ => T::default(),
+        };
+        let before = canonical(&state);
+        let result = change(&mut state, intact);
+        let content = canonical(&state)
+            .ok_or_else(|| AuthError::Failed(format!("Failed to serialize {}", name(path))))?;
+
+        let generation = if intact && before.as_deref() == Some(content.as_str()) {
+            last
+        } else {
+            let generation = last + 1;
+            if result.is_ok() {
+                seal.generation.set(generation)?;
+            }
+            generation
+        };
+        let mac = seal.mac(generation, &content);
+        *file = Some(Sealed { generation, state, mac });
+        result
+    })
+}
+
+/// Exclusive lock on `<path>.lock`, released when the file is dropped
+fn lock(path: &Path) -> Result<File, AuthError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| AuthError::Failed(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(path))
+        .map_err(|e| AuthError::Failed(format!("Failed to open lock for {}: {}", name(path), e)))?;
+    file.lock().map_err(|e| AuthError::Failed(format!("Failed to lock {}: {}", name(path), e)))?;
+    Ok(file)
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".lock");
+    PathBuf::from(name)
+}
+
+/// Write aside and rename into place, so readers never see a partial file
+fn write(path: &Path, json: &str) -> Result<(), AuthError> {
+    let staging = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&staging, json).map_err(|e| AuthError::Failed(format!("Failed to save {}: {}", name(path), e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&staging, fs::Permissions::from_mode(0o600));
+    }
+    fs::rename(&staging, path).map_err(|e| {
+        let _ = fs::remove_file(&staging);
+        AuthError::Failed(format!("Failed to save {}: {}", name(path), e))
+    })
+}
+
+fn name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
@@ -2,6 +2,9 @@
 //!
 //! Sends a message to a Telegram chat and waits for /allow or /deny response.
 //! This is an async authenticator that uses the AsyncAuthenticator trait.
+//!
+//! With a challenge, only `/allow <code>` approves, so a reply meant for
+//! another request cannot approve this one.
 
 use std::time::Duration;
 
@@ -12,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use super::keyring::SecureKeyring;
 use super::manager::AsyncAuthenticator;
 use super::{AuthError, AuthResult};
+use crate::rules::redact_secrets;
 
 /// Default timeout for waiting for Telegram response
 const DEFAULT_TIMEOUT_SECONDS: u64 = 60;
@@ -23,6 +27,7 @@ const TELEGRAM_API_BASE: &str = "https://api.telegram.org/bot";
 pub struct TelegramAuth {
     chat_id: String,
     timeout: Duration,
+    challenge: Option<String>,
 }
 
 impl TelegramAuth {
@@ -30,6 +35,7 @@ impl TelegramAuth {
         Self {
             chat_id: chat_id.to_string(),
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
+            challenge: None,
         }
     }
 
@@ -38,6 +44,12 @@ impl TelegramAuth {
         self
     }
 
+    /// Require approvals to echo a challenge code
+    pub fn with_challenge(mut self, challenge: Option<String>) -> Self {
+        self.challenge = challenge;
+        self
+    }
+
     /// Setup Telegram authentication
     pub fn setup(bot_token: &str) -> Result<(), AuthError> {
         SecureKeyring::set_telegram_token(bot_token)
@@ -144,12 +156,15 @@ impl TelegramAuth {
                             let msg_time = msg.date.unwrap_or(0);
                             if msg_time >= request_time {
                                 if let Some(text) = msg.text {
-                                    let text_lower = text.to_lowercase();
-                                    if text_lower.starts_with("/allow") || text_lower == "allow" || text_lower == "yes" {
-                                        return Ok(true);
-                                    }
-                                    if text_lower.starts_with("/deny") || text_lower == "deny" || text_lower == "no" {
-                                        return Ok(false);
+                                    match parse_reply(&text, self.challenge.as_deref()) {
+                                        Reply::Allow => return Ok(true),
+                                        Reply::Deny => return Ok(false),
+                                        Reply::MissingChallenge => {
+                                            let _ = self
+                                                .send_message(token, "Include the challenge code: /allow &lt;code&gt;")
+                                                .await;
+                                        }
+                                        Reply::Other => {}
                                     }
                                 }
                             }
@@ -173,6 +188,7 @@ impl AsyncAuthenticator for TelegramAuth {
     }
 
     async fn authenticate_async(&self, command: &str) -> AuthResult {
+        let command = redact_secrets(command);
         eprintln!("{} {}", "Command:".yellow(), command);
         eprintln!("{}", "Waiting for Telegram approval...".cyan());
 
@@ -185,11 +201,20 @@ impl AsyncAuthenticator for TelegramAuth {
             .unwrap_or(0);
 
         // Send approval request
+        let reply = match &self.challenge {
+            Some(challenge) => format!(
+                "<b>Challenge:</b> <code>{}</code>\n\nReply with /allow {} or /deny",
+                html_escape(challenge),
+                html_escape(challenge)
+            ),
+            None => "Reply with /allow or /deny".to_string(),
+        };
         let message = format!(
             "🔐 <b>Veto Authorization Request</b>\n\n\
              <b>Command:</b>\n<code>{}</code>\n\n\
-             Reply with /allow or /deny",
-            html_escape(command)
+             {}",
+            html_escape(&command),
+            reply
         );
 
         let _msg_id = self.send_message(&token, &message).await?;
@@ -261,6 +286,36 @@ struct Update {
     message: Option<Message>,
 }
 
+/// How a chat message answers an approval request
+#[derive(Debug, PartialEq, Eq)]
+enum Reply {
+    Allow,
+    Deny,
+    /// An approval without the challenge code it has to echo
+    MissingChallenge,
+    Other,
+}
+
+fn parse_reply(text: &str, challenge: Option<&str>) -> Reply {
+    let text_lower = text.trim().to_lowercase();
+    if text_lower.starts_with("/deny") || text_lower == "deny" || text_lower == "no" {
+        return Reply::Deny;
+    }
+    let mut words = text_lower.split_whitespace();
+    let allow = match words.next() {
+        Some(first) => first.starts_with("/allow") || first == "allow" || first == "yes",
+        None => false,
+    };
+    if !allow {
+        return Reply::Other;
+    }
+    match challenge {
+        None => Reply::Allow,
+        Some(code) if words.next().is_some_and(|word| word.eq_ignore_ascii_case(code)) => Reply::Allow,
+        Some(_) => Reply::MissingChallenge,
+    }
+}
+
 /// Escape HTML special characters for Telegram
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -278,6 +333,18 @@ mod tests {
         assert_eq!(html_escape("a & b"), "a &amp; b");
     }
 
+    #[test]
+    fn test_parse_reply_with_challenge() {
+        assert_eq!(parse_reply("/allow", None), Reply::Allow);
+        assert_eq!(parse_reply("Yes", None), Reply::Allow);
+        assert_eq!(parse_reply("/deny", Some("4827")), Reply::Deny);
+        assert_eq!(parse_reply("/allow", Some("4827")), Reply::MissingChallenge);
+        assert_eq!(parse_reply("/allow 1111", Some("4827")), Reply::MissingChallenge);
+        assert_eq!(parse_reply("/allow 4827", Some("4827")), Reply::Allow);
+        assert_eq!(parse_reply("allow ab2c", Some("AB2C")), Reply::Allow);
+        assert_eq!(parse_reply("hello", Some("4827")), Reply::Other);
+    }
+
     #[test]
     fn test_with_timeout() {
         let auth = TelegramAuth::new("12345").with_timeout(120);
@@ -2,17 +2,106 @@
 //!
 //! Compatible with Google Authenticator, Authy, and other TOTP apps.
 //! Uses RFC 6238 standard with SHA1, 6 digits, 30 second period.
+//!
+//! Each time-step is accepted once: the last used step is persisted next
+//! to the file keyring, so a code the user typed into a chat cannot be
+//! replayed for another command while it is still valid. The file is
+//! sealed (see [`state`]); when it was deleted, edited or restored from an
+//! older copy, every step up to the current one counts as used.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use colored::Colorize;
 use dialoguer::Input;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use totp_rs::{Algorithm, Secret, TOTP};
 
-use super::keyring::SecureKeyring;
-use super::{AuthError, AuthResult, Authenticator};
+use super::keyring::{keys, SecureKeyring};
+use super::lockout;
+use super::state::{self, Seal};
+use super::{AuthError, AuthResult, Authenticator, RecoveryAuth};
+use crate::config::loader::load_config;
 
 /// Default issuer name for TOTP
 const DEFAULT_ISSUER: &str = "veto";
 
+/// TOTP period in seconds
+pub const TOTP_STEP_SECONDS: u64 = 30;
+
+/// Default number of steps accepted on either side of the current one
+const DEFAULT_SKEW: u8 = 1;
+
+/// `[auth.totp]` settings used during verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TotpSettings {
+    pub skew: u8,
+    pub bind_command: bool,
+}
+
+impl Default for TotpSettings {
+    fn default() -> Self {
+        Self { skew: DEFAULT_SKEW, bind_command: false }
+    }
+}
+
+impl TotpSettings {
+    pub fn load() -> Self {
+        let totp = load_config().ok().and_then(|c| c.auth).and_then(|a| a.totp);
+        Self {
+            skew: totp.as_ref().and_then(|t| t.skew).unwrap_or(DEFAULT_SKEW),
+            bind_command: totp.and_then(|t| t.bind_command).unwrap_or(false),
+        }
+    }
+}
+
+/// Replay and drift state, persisted across verifications
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TotpState {
+    /// Highest time-step accepted so far
+    pub last_step: u64,
+    /// Hash of the command the last step approved
+    pub command_hash: Option<String>,
+    /// Steps between the last accepted code and the local clock
+    /// (negative: the authenticator is behind)
+    pub last_offset: i64,
+}
+
+impl TotpState {
+    fn path() -> Result<PathBuf, AuthError> {
+        SecureKeyring::secrets_dir()
+            .map(|dir| dir.join("totp_state.json"))
+            .map_err(|e| AuthError::Failed(e.to_string()))
+    }
+
+    fn seal() -> Result<Seal, AuthError> {
+        Seal::open("totp", keys::TOTP_GENERATION)
+    }
+
+    /// The saved state; `None` when it cannot be trusted
+    pub fn load() -> Option<Self> {
+        let (path, seal) = (Self::path().ok()?, Self::seal().ok()?);
+        state::load_sealed(&path, &seal)
+    }
+
+    /// Start over with no step used, for a new secret
+    fn reset() -> Result<(), AuthError> {
+        state::update_sealed(&Self::path()?, &Self::seal()?, |state: &mut TotpState, _| {
+            *state = TotpState::default();
+            Ok(())
+        })
+    }
+
+    /// Whether a code for `step` may be accepted for `command_hash`
+    fn accepts(&self, step: u64, command_hash: Option<&str>, bind_command: bool) -> bool {
+        if step > self.last_step {
+            return true;
+        }
+        bind_command && step == self.last_step && command_hash.is_some() && self.command_hash.as_deref() == command_hash
+    }
+}
+
 /// TOTP authenticator
 pub struct TotpAuth;
 
@@ -45,6 +134,7 @@ impl TotpAuth {
         // Store secret in keychain
         SecureKeyring::set_totp_secret(&secret_base32)
             .map_err(|e| AuthError::Failed(format!("Failed to store TOTP secret: {}", e)))?;
+        TotpState::reset()?;
 
         Ok(SetupResult {
             secret: secret_base32,
@@ -54,6 +144,7 @@ impl TotpAuth {
 
     /// Delete stored TOTP secret
     pub fn delete() -> Result<(), AuthError> {
+        TotpState::reset()?;
         SecureKeyring::delete_totp()
             .map_err(|e| AuthError::Failed(format!("Failed to delete TOTP: {}", e)))
     }
@@ -61,8 +152,27 @@ impl TotpAuth {
     /// Verify a TOTP code (static method for setup verification)
     /// Does not prompt for input - use this when you already have the code
     pub fn verify(code: &str) -> Result<bool, AuthError> {
+        Self::verify_for(code, None)
+    }
+
+    /// Verify a TOTP code approving `command`
+    ///
+    /// A code from an already used time-step is rejected, unless
+    /// `bind_command` is set and it approved this same command.
+    pub fn verify_for(code: &str, command: Option<&str>) -> Result<bool, AuthError> {
         let totp = Self::create_totp()?;
-        Ok(totp.check_current(code).unwrap_or(false))
+        let settings = TotpSettings::load();
+
+        lockout::guard("totp", || {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            let Some(step) = matching_step(&totp, code, now, settings.skew) else {
+                return Ok(false);
+            };
+
+            let (path, seal) = (TotpState::path()?, TotpState::seal()?);
+            use_step(&path, &seal, step, command.map(hash_command), settings.bind_command, now / TOTP_STEP_SECONDS)?;
+            Ok(true)
+        })
     }
 
     /// Create TOTP instance from stored secret
@@ -87,12 +197,9 @@ impl TotpAuth {
         .map_err(|e| AuthError::Failed(format!("Failed to create TOTP: {}", e)))
     }
 
-    /// Verify a TOTP code
-    fn verify_code(&self, code: &str) -> Result<bool, AuthError> {
-        let totp = Self::create_totp()?;
-
-        // Check current and adjacent time windows for clock skew tolerance
-        Ok(totp.check_current(code).unwrap_or(false))
+    /// Verify a TOTP code for a command
+    fn verify_code(&self, code: &str, command: &str) -> Result<bool, AuthError> {
+        Self::verify_for(code, Some(command))
     }
 }
 
@@ -107,13 +214,15 @@ impl Authenticator for TotpAuth {
         SecureKeyring::has_totp()
     }
 
-    fn authenticate(&self, _command: &str) -> AuthResult {
+    fn authenticate(&self, command: &str) -> AuthResult {
         println!("{}", "TOTP verification required".red());
 
+        // Without the authenticator at hand, a recovery code works too
+        let recovery = RecoveryAuth::remaining() > 0;
         let code: String = Input::new()
-            .with_prompt("Enter 6-digit code")
+            .with_prompt(if recovery { "Enter 6-digit code (or a recovery code)" } else { "Enter 6-digit code" })
             .validate_with(|input: &String| {
-                if input.len() == 6 && input.chars().all(|c| c.is_ascii_digit()) {
+                if is_totp_code(input) || (recovery && input.len() > 6) {
                     Ok(())
                 } else {
                     Err("Code must be exactly 6 digits")
@@ -122,7 +231,17 @@ impl Authenticator for TotpAuth {
             .interact_text()
             .map_err(|_| AuthError::Cancelled)?;
 
-        match self.verify_code(&code) {
+        if !is_totp_code(&code) {
+            return match RecoveryAuth::verify(&code)? {
+                true => {
+                    println!("{} ({} left)", "✓ Recovery code accepted".green(), RecoveryAuth::remaining());
+                    Ok(true)
+                }
+                false => Err(AuthError::Failed("Invalid recovery code".to_string())),
+            };
+        }
+
+        match self.verify_code(&code, command) {
             Ok(true) => {
                 println!("{}", "✓ TOTP verified".green());
                 Ok(true)
@@ -137,6 +256,63 @@ impl Authenticator for TotpAuth {
     }
 }
 
+/// Record `step` as used, unless it already was. The check and the write
+/// happen under the state file lock, so two parallel verifications cannot
+/// both accept the same step. A state that cannot be trusted is replaced
+/// by one with every step up to `current` used, and the code is refused.
+fn use_step(
+    path: &Path,
+    seal: &Seal,
+    step: u64,
+    command_hash: Option<String>,
+    bind_command: bool,
+    current: u64,
+) -> Result<(), AuthError> {
+    let refused = state::update_sealed(path, seal, |state: &mut TotpState, intact| {
+        if !intact {
+            *state = TotpState { last_step: current.max(step), command_hash: None, last_offset: 0 };
+            return Ok(Some("TOTP state was deleted or altered, so codes already shown are refused. Wait for the next code."));
+        }
+        if !state.accepts(step, command_hash.as_deref(), bind_command) {
+            return Ok(Some("TOTP code already used. Wait for the next code."));
+        }
+        if step > state.last_step {
+            *state = TotpState { last_step: step, command_hash, last_offset: step as i64 - current as i64 };
+        }
+        Ok(None)
+    })?;
+    match refused {
+        Some(reason) => Err(AuthError::Failed(reason.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// The time-step within `skew` steps of `now` that `code` belongs to,
+/// nearest first
+fn matching_step(totp: &TOTP, code: &str, now: u64, skew: u8) -> Option<u64> {
+    let current = now / TOTP_STEP_SECONDS;
+    let mut offsets = vec![0i64];
+    for distance in 1..=skew as i64 {
+        offsets.extend([-distance, distance]);
+    }
+    offsets.into_iter().find_map(|offset| {
+        let step = current.checked_add_signed(offset)?;
+        constant_time_eq(totp.generate(step * TOTP_STEP_SECONDS).as_bytes(), code.as_bytes()).then_some(step)
+    })
+}
+
+fn is_totp_code(input: &str) -> bool {
+    input.len() == 6 && input.chars().all(|c| c.is_ascii_digit())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn hash_command(command: &str) -> String {
+    format!("{:x}", Sha256::digest(command.as_bytes()))
+}
+
 /// Result from TOTP setup
 pub struct SetupResult {
     /// Base32 encoded secret (for manual entry)
@@ -164,6 +340,66 @@ impl SetupResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_matching_step_and_replay() {
+        let totp = TOTP::new(Algorithm::SHA1, 6, 1, 30, b"12345678901234567890".to_vec(), None, "t".to_string()).unwrap();
+        let now = 1_000_000_020;
+        let current = now / TOTP_STEP_SECONDS;
+        let previous = totp.generate((current - 1) * TOTP_STEP_SECONDS);
+        assert_eq!(matching_step(&totp, &previous, now, 1), Some(current - 1));
+        assert_eq!(matching_step(&totp, &previous, now, 0), None);
+        assert_eq!(matching_step(&totp, "000000x", now, 1), None);
+
+        let state = TotpState { last_step: current, command_hash: Some(hash_command("ls")), last_offset: 0 };
+        assert!(state.accepts(current + 1, None, false));
+        assert!(!state.accepts(current, Some(&hash_command("ls")), false));
+        assert!(state.accepts(current, Some(&hash_command("ls")), true));
+        assert!(!state.accepts(current, Some(&hash_command("rm -rf x")), true));
+        assert!(!state.accepts(current - 1, Some(&hash_command("ls")), true));
+    }
+
+    #[test]
+    fn test_parallel_uses_of_one_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("totp_state.json");
+        let seal = Seal::memory("totp");
+        let use_step = |step: u64| use_step(&path, &seal, step, None, false, 42);
+
+        let accepted = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8).map(|_| s.spawn(|| use_step(42))).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).filter(Result::is_ok).count()
+        });
+        assert_eq!(accepted, 1);
+        assert!(use_step(42).is_err());
+        assert!(use_step(43).is_ok());
+    }
+
+    #[test]
+    fn test_untrusted_state_refuses_shown_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("totp_state.json");
+        let seal = Seal::memory("totp");
+        let use_step = |step: u64, current: u64| use_step(&path, &seal, step, None, false, current);
+
+        assert!(use_step(40, 40).is_ok());
+        let snapshot = fs::read_to_string(&path).unwrap();
+        assert!(use_step(41, 41).is_ok());
+
+        // Deleted: the code seen at step 41 must not work again, nor one
+        // from the current step, until a later step
+        fs::remove_file(&path).unwrap();
+        assert!(use_step(42, 42).is_err());
+        assert!(use_step(42, 42).is_err());
+        assert!(use_step(43, 43).is_ok());
+
+        // Restored from an older copy
+        fs::write(&path, &snapshot).unwrap();
+        assert!(use_step(44, 44).is_err());
+        assert!(use_step(45, 45).is_ok());
+        assert_eq!(state::load_sealed::<TotpState>(&path, &seal).unwrap().last_step, 45);
+    }
 
     #[test]
     #[ignore = "requires system keychain"]
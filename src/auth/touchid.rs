@@ -6,6 +6,7 @@
 use colored::Colorize;
 
 use super::{AuthError, AuthResult, Authenticator, AuthContext};
+use crate::rules::redact_secrets;
 
 /// Default prompt for Touch ID
 const DEFAULT_PROMPT: &str = "Veto: Approve running this command?";
@@ -23,17 +24,23 @@ impl TouchIdAuth {
     }
 
     /// Build prompt with context information
-    fn build_prompt(&self, command: &str, _context: Option<&AuthContext>) -> String {
+    fn build_prompt(&self, command: &str, context: Option<&AuthContext>) -> String {
         let mut lines = vec![self.prompt.clone()];
 
         // Truncate command for display
+        let command = redact_secrets(command);
         let display_cmd = if command.len() > 80 {
             format!("{}...", &command[..77])
         } else {
-            command.to_string()
+            command
         };
         lines.push(format!("Command: {}", display_cmd));
 
+        // Shown only in the system prompt, never on stderr where the agent reads
+        if let Some(challenge) = context.and_then(|c| c.challenge.as_ref()) {
+            lines.push(format!("Challenge: {}", challenge));
+        }
+
         lines.join("\n")
     }
 
@@ -160,7 +167,7 @@ impl Authenticator for TouchIdAuth {
     }
 
     fn authenticate(&self, command: &str) -> AuthResult {
-        eprintln!("{} {}", "Command:".yellow(), command);
+        eprintln!("{} {}", "Command:".yellow(), redact_secrets(command));
         eprintln!("{}", "Touch ID verification required".cyan());
 
         self.do_authenticate(&self.prompt)
@@ -170,7 +177,7 @@ impl Authenticator for TouchIdAuth {
         let prompt = self.build_prompt(command, Some(context));
 
         // Print context info to stderr for visibility
-        eprintln!("{} {}", "Command:".yellow(), command);
+        eprintln!("{} {}", "Command:".yellow(), redact_secrets(command));
         if let Some(ref tool) = context.tool_name {
             eprintln!("{} {}", "Tool:".yellow(), tool);
         }
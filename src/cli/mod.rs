@@ -1,5 +1,5 @@
-use clap::{Args, Parser, Subcommand};
-use crate::commands::AuthCommands;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use crate::commands::{AuthCommands, DeniedCommands, GrantsCommands};
 
 #[derive(Parser)]
 #[command(name = "veto")]
@@ -46,11 +46,23 @@ pub enum Commands {
     Doctor,
     /// Start interactive shell wrapper
     Shell,
+    /// Run as an MCP server over stdio
+    Mcp,
     /// Manage authentication methods
     Auth {
         #[command(subcommand)]
         command: AuthCommands,
     },
+    /// List, add or revoke time-limited approval grants
+    Grants {
+        #[command(subcommand)]
+        command: Option<GrantsCommands>,
+    },
+    /// List or lift denials of commands the user rejected
+    Denied {
+        #[command(subcommand)]
+        command: Option<DeniedCommands>,
+    },
     /// Setup integrations with AI tools
     Setup {
         #[command(subcommand)]
@@ -90,25 +102,33 @@ pub struct LogArgs {
 
 #[derive(Args)]
 pub struct GateArgs {
-    /// Command to verify (optional if using --claude/--gemini/--cursor)
+    /// Command to verify (optional if using --claude/--codex/--gemini/--cursor)
     pub command: Option<String>,
 
     /// Read command from Claude Code stdin JSON format
-    #[arg(long, conflicts_with_all = ["command", "opencode", "gemini", "cursor"])]
+    #[arg(long, conflicts_with_all = ["command", "opencode", "gemini", "cursor", "codex"])]
     pub claude: bool,
 
+    /// Read command from Codex CLI stdin JSON format
+    #[arg(long, conflicts_with_all = ["command", "opencode", "gemini", "cursor", "claude"])]
+    pub codex: bool,
+
     /// Read command from Gemini CLI stdin JSON format
-    #[arg(long, conflicts_with_all = ["command", "opencode", "claude", "cursor"])]
+    #[arg(long, conflicts_with_all = ["command", "opencode", "claude", "cursor", "codex"])]
     pub gemini: bool,
 
     /// OpenCode mode - use dialog/touchid auth, don't suggest env var bypass
-    #[arg(long, conflicts_with_all = ["claude", "gemini", "cursor"])]
+    #[arg(long, conflicts_with_all = ["claude", "gemini", "cursor", "codex"])]
     pub opencode: bool,
 
     /// Cursor CLI mode - read command from stdin JSON and return hooks response
-    #[arg(long, conflicts_with_all = ["claude", "gemini", "opencode"])]
+    #[arg(long, conflicts_with_all = ["claude", "gemini", "opencode", "codex"])]
     pub cursor: bool,
 
+    /// Use a hook adapter from ~/.veto/adapters/<NAME>.toml
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["claude", "gemini", "opencode", "cursor", "codex"])]
+    pub adapter: Option<String>,
+
     /// File operation mode (for write_file/edit_file hooks)
     #[arg(long)]
     pub file_op: bool,
@@ -129,27 +149,41 @@ pub struct GateArgs {
 #[derive(Subcommand)]
 pub enum SetupCommands {
     /// Setup Claude Code hooks integration
-    Claude {
-        /// Remove veto hooks from Claude Code
-        #[arg(long)]
-        uninstall: bool,
-    },
+    Claude(SetupArgs),
+    /// Setup Codex CLI hooks integration
+    Codex(SetupArgs),
     /// Setup Gemini CLI hooks integration
-    Gemini {
-        /// Remove veto hooks from Gemini CLI
-        #[arg(long)]
-        uninstall: bool,
-    },
+    Gemini(SetupArgs),
     /// Setup OpenCode plugin integration
-    Opencode {
-        /// Remove veto plugin from OpenCode
-        #[arg(long)]
-        uninstall: bool,
-    },
+    Opencode(SetupArgs),
     /// Setup Cursor CLI hooks integration
-    Cursor {
-        /// Remove veto hook from Cursor CLI
-        #[arg(long)]
-        uninstall: bool,
-    },
+    Cursor(SetupArgs),
+    /// Show which integrations are installed and up to date
+    Status,
+    /// Update installed integrations to this version's hooks
+    Refresh,
+}
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct SetupArgs {
+    /// Remove veto hooks instead of installing them
+    #[arg(long)]
+    pub uninstall: bool,
+
+    /// Print the settings change as a diff without writing it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Install into user settings or the current project's settings
+    #[arg(long, value_enum, default_value_t = SetupScope::User)]
+    pub scope: SetupScope,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SetupScope {
+    /// ~/.claude, ~/.gemini, ...
+    #[default]
+    User,
+    /// .claude, .gemini, ... in the current repository
+    Project,
 }
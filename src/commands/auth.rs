@@ -4,11 +4,12 @@ use clap::Subcommand;
 use colored::Colorize;
 use dialoguer::{Input, Password};
 
-use crate::auth::{
-    AuthenticatorFactory, PinAuth, TotpAuth, TelegramAuth,
-    keyring::SecureKeyring,
+use veto::auth::{
+    AuthFactor, AuthenticatorFactory, PinAuth, RecoveryAuth, TotpAuth, TelegramAuth, RECOVERY_CODE_COUNT,
+    keyring::SecureKeyring, lockout, manager::method_strength,
 };
-use crate::config::loader::load_config;
+use veto::config::loader::load_config;
+use veto::pipeline;
 
 #[derive(Subcommand)]
 pub enum AuthCommands {
@@ -25,9 +26,15 @@ pub enum AuthCommands {
     /// Setup Telegram bot
     SetupTelegram,
 
+    /// Manage one-time recovery codes (backup for TOTP)
+    Recovery {
+        #[command(subcommand)]
+        command: RecoveryCommands,
+    },
+
     /// Test an authentication method
     Test {
-        /// Method to test (confirm, pin, totp, touchid, telegram)
+        /// Method to test (confirm, pin, totp, recovery, touchid, telegram)
         method: String,
     },
 
@@ -36,9 +43,28 @@ pub enum AuthCommands {
 
     /// Remove an authentication method
     Remove {
-        /// Method to remove (pin, totp, telegram)
+        /// Method to remove (pin, totp, recovery, telegram)
         method: String,
     },
+
+    /// Unlock PIN, TOTP or recovery codes after too many failed attempts
+    Unlock {
+        /// Method to unlock (pin, totp, recovery); defaults to every locked method
+        method: Option<String>,
+
+        /// Verify with this method (must be at least as strong as the locked one)
+        #[arg(long)]
+        with: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RecoveryCommands {
+    /// Replace all recovery codes with a new set (requires TOTP or a recovery code)
+    Regenerate,
+
+    /// Show how many recovery codes are left
+    Status,
 }
 
 pub fn run_auth_command(cmd: AuthCommands) -> Result<(), Box<dyn std::error::Error>> {
@@ -46,9 +72,14 @@ pub fn run_auth_command(cmd: AuthCommands) -> Result<(), Box<dyn std::error::Err
         AuthCommands::SetPin => run_set_pin(),
         AuthCommands::SetupTotp { account } => run_setup_totp(&account),
         AuthCommands::SetupTelegram => run_setup_telegram(),
+        AuthCommands::Recovery { command } => match command {
+            RecoveryCommands::Regenerate => run_recovery_regenerate(),
+            RecoveryCommands::Status => run_recovery_status(),
+        },
         AuthCommands::Test { method } => run_test(&method),
         AuthCommands::List => run_list(),
         AuthCommands::Remove { method } => run_remove(&method),
+        AuthCommands::Unlock { method, with } => run_unlock(method.as_deref(), with.as_deref()),
     }
 }
 
@@ -150,6 +181,7 @@ fn run_setup_totp(account: &str) -> Result<(), Box<dyn std::error::Error>> {
         Ok(true) => {
             println!();
             println!("{}", "✓ TOTP configured successfully!".green());
+            print_recovery_codes(&RecoveryAuth::generate()?);
             Ok(())
         }
         Ok(false) => {
@@ -165,8 +197,50 @@ fn run_setup_totp(account: &str) -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+/// Show a new set of recovery codes, once
+fn print_recovery_codes(codes: &[String]) {
+    println!();
+    println!("{}", "Recovery codes:".bold());
+    for code in codes {
+        println!("  {}", code.cyan());
+    }
+    println!();
+    println!("{}", "Store these somewhere safe; they will not be shown again.".yellow());
+    println!("Each code works once, wherever TOTP is accepted (VETO_RECOVERY=<code> in hooks).");
+}
+
+/// Replace the recovery codes after verifying with TOTP (or a recovery code)
+fn run_recovery_regenerate() -> Result<(), Box<dyn std::error::Error>> {
+    if !SecureKeyring::has_totp() {
+        return Err("Recovery codes back up TOTP. Run 'veto auth setup-totp' first.".into());
+    }
+
+    let config = load_config()?;
+    println!("{}", "Regenerating recovery codes".cyan().bold());
+    pipeline::run_auth_chain(&config, &[AuthFactor::single("totp")], "veto auth recovery regenerate", None)?;
+
+    print_recovery_codes(&RecoveryAuth::generate()?);
+    println!("{}", "Previous recovery codes no longer work.".dimmed());
+    Ok(())
+}
+
+fn run_recovery_status() -> Result<(), Box<dyn std::error::Error>> {
+    let remaining = RecoveryAuth::remaining();
+    print!("{} ", "Recovery codes:".bold());
+    match remaining {
+        0 if SecureKeyring::has_totp() => println!("{}", "none (run 'veto auth recovery regenerate')".dimmed()),
+        0 => println!("{}", "none (generated by 'veto auth setup-totp')".dimmed()),
+        n if n <= 2 => println!("{} of {} left, regenerate soon", n.to_string().yellow(), RECOVERY_CODE_COUNT),
+        n => println!("{} of {} left", n.to_string().green(), RECOVERY_CODE_COUNT),
+    }
+    if lockout::is_locked("recovery") {
+        println!("{}", "Locked after too many failed attempts (run 'veto auth unlock recovery')".red());
+    }
+    Ok(())
+}
+
 fn run_setup_telegram() -> Result<(), Box<dyn std::error::Error>> {
-    use crate::config::loader::update_telegram_config;
+    use veto::config::loader::update_telegram_config;
 
     println!("{}", "Setting up Telegram authentication".cyan().bold());
     println!();
@@ -340,8 +414,8 @@ async fn wait_for_message(token: &str, timeout_secs: u64) -> Result<String, Box<
 }
 
 fn run_test(method: &str) -> Result<(), Box<dyn std::error::Error>> {
-    use crate::auth::{Authenticator, ConfirmAuth, TouchIdAuth};
-    use crate::auth::manager::AsyncAuthBridge;
+    use veto::auth::{Authenticator, ConfirmAuth, TouchIdAuth};
+    use veto::auth::manager::AsyncAuthBridge;
 
     println!("{} {}", "Testing authentication method:".cyan().bold(), method);
     println!();
@@ -367,6 +441,13 @@ fn run_test(method: &str) -> Result<(), Box<dyn std::error::Error>> {
             let auth = TotpAuth::new();
             auth.authenticate(test_command)
         }
+        "recovery" => {
+            if !AuthenticatorFactory::is_available("recovery") {
+                return Err("No recovery codes. Run 'veto auth recovery regenerate' first.".into());
+            }
+            // Testing uses up the code
+            RecoveryAuth::new().authenticate(test_command)
+        }
         "touchid" => {
             if !AuthenticatorFactory::is_available("touchid") {
                 return Err("Touch ID is only available on macOS.".into());
@@ -423,7 +504,9 @@ fn run_list() -> Result<(), Box<dyn std::error::Error>> {
             "✗".red()
         };
 
-        let availability = if available {
+        let availability = if available && lockout::is_locked(name) {
+            "locked (run 'veto auth unlock')".red()
+        } else if available {
             "configured".green()
         } else {
             "not configured".dimmed()
@@ -443,12 +526,15 @@ fn run_list() -> Result<(), Box<dyn std::error::Error>> {
         if let Some(levels) = auth.levels {
             println!("{}", "Level mappings:".bold());
             for (level, method) in levels {
-                let method_str = match method {
-                    crate::config::AuthMethod::Single(m) => m,
-                    crate::config::AuthMethod::Multiple(ms) => ms.join(", "),
-                };
-                println!("  {} = {}", level, method_str);
+                println!("  {} = {}", level, method);
+            }
+        }
+        if let Some(fallback) = &auth.fallback {
+            println!("{}", "Fallbacks:".bold());
+            for (method, next) in fallback {
+                println!("  {} → {}", method, next);
             }
+            println!("  floor: {}", auth.fallback_floor.as_deref().unwrap_or("pin"));
         }
     }
 
@@ -471,11 +557,18 @@ fn run_remove(method: &str) -> Result<(), Box<dyn std::error::Error>> {
     match method {
         "pin" => {
             PinAuth::delete_pin()?;
+            lockout::reset("pin")?;
             println!("{}", "✓ PIN removed.".green());
         }
         "totp" => {
             TotpAuth::delete()?;
-            println!("{}", "✓ TOTP removed.".green());
+            lockout::reset("totp")?;
+            RecoveryAuth::delete()?;
+            println!("{}", "✓ TOTP and its recovery codes removed.".green());
+        }
+        "recovery" => {
+            RecoveryAuth::delete()?;
+            println!("{}", "✓ Recovery codes removed.".green());
         }
         "telegram" => {
             TelegramAuth::delete()?;
@@ -491,3 +584,53 @@ fn run_remove(method: &str) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Methods that can unlock a lockout, strongest first
+const UNLOCK_METHODS: &[&str] = &["touchid", "telegram", "totp", "recovery", "pin"];
+
+/// Clear failed-attempt counters after verifying with a different method
+/// at least as strong as every locked one
+fn run_unlock(method: Option<&str>, with: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let locked: Vec<&str> = match method {
+        Some(m) if lockout::LIMITED_METHODS.contains(&m) => vec![m],
+        Some(m) => return Err(format!("{} has no attempt limit (pin, totp, recovery).", m).into()),
+        None => lockout::LIMITED_METHODS.to_vec(),
+    };
+    let locked: Vec<&str> = locked.into_iter().filter(|m| lockout::state(m).failures > 0).collect();
+    if locked.is_empty() {
+        println!("Nothing to unlock.");
+        return Ok(());
+    }
+
+    let config = load_config()?;
+    let required = locked.iter().map(|m| method_strength(m)).max().unwrap_or(0);
+    let can_unlock = |m: &str| !locked.contains(&m) && method_strength(m) >= required;
+    let names = locked.join(", ");
+
+    let verifier = match with {
+        Some(m) if !can_unlock(m) => {
+            return Err(format!("{} cannot unlock {}: use a different method at least as strong.", m, names).into());
+        }
+        Some(m) => m,
+        None => UNLOCK_METHODS
+            .iter()
+            .copied()
+            .find(|m| can_unlock(m) && pipeline::method_available(&config, m))
+            .ok_or_else(|| {
+                format!(
+                    "No method strong enough to unlock {}. Set up Touch ID or Telegram, or remove and set up the method again ('veto auth remove {}').",
+                    names, locked[0]
+                )
+            })?,
+    };
+
+    println!("{} {} (verify with {})", "Unlocking:".cyan().bold(), names, verifier);
+    // No fallbacks: they could reach a method weaker than the locked one
+    pipeline::run_auth_method(&config, verifier, "veto auth unlock", None)?;
+
+    for m in &locked {
+        lockout::reset(m)?;
+    }
+    println!("{}", format!("✓ Unlocked {}.", names).green());
+    Ok(())
+}
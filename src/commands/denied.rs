@@ -0,0 +1,95 @@
+//! Denied subcommands for the deny cache of rejected commands
+
+use clap::Subcommand;
+use colored::Colorize;
+
+use veto::audit::{self, DeniedCommand};
+use veto::config::loader::load_config;
+use veto::pipeline;
+use veto::rules::redact_secrets;
+
+#[derive(Subcommand)]
+pub enum DeniedCommands {
+    /// List commands blocked from retries (default)
+    List,
+
+    /// Allow retries of one rejected command (requires auth)
+    Remove {
+        /// Entry id (see 'veto denied list')
+        id: String,
+    },
+
+    /// Allow retries of every rejected command (requires auth)
+    Clear,
+}
+
+pub fn run_denied_command(cmd: Option<DeniedCommands>) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd.unwrap_or(DeniedCommands::List) {
+        DeniedCommands::List => run_list(),
+        DeniedCommands::Remove { id } => run_remove(&id),
+        DeniedCommands::Clear => run_clear(),
+    }
+}
+
+fn run_list() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = audit::denied_commands();
+    if entries.is_empty() {
+        println!("{}", "No denied commands.".dimmed());
+        return Ok(());
+    }
+
+    println!("{}", "Denied commands:".bold());
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    for entry in &entries {
+        println!(
+            "  {} {} {} ({} min left)",
+            entry.id.cyan(),
+            entry.level,
+            redact_secrets(&entry.command).bold(),
+            entry.expires_at.saturating_sub(now).div_ceil(60)
+        );
+        println!("    {} {}", "session".dimmed(), entry.session_id.as_deref().unwrap_or("none"));
+    }
+    Ok(())
+}
+
+/// Lifting a denial is a decision the user makes: verify them with the
+/// auth methods for the highest level being lifted, so an agent setting a
+/// variable is not enough
+fn verify_user(entries: &[&DeniedCommand], action: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(level) = entries.iter().map(|e| e.level).max() else {
+        return Ok(());
+    };
+    let config = load_config()?;
+    let factors = pipeline::auth_methods(&config, level, None);
+    if !factors.is_empty() {
+        pipeline::run_auth_chain(&config, &factors, action, None)?;
+    }
+    Ok(())
+}
+
+fn run_remove(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = audit::denied_commands();
+    let Some(entry) = entries.iter().find(|e| e.id == id) else {
+        return Err(format!("No denied command '{}'", id).into());
+    };
+    verify_user(&[entry], &format!("veto denied remove {}", id))?;
+
+    if audit::remove_denied_command(id)?.is_some() {
+        println!("{} Retries allowed for {}", "✓".green(), redact_secrets(&entry.command));
+    }
+    Ok(())
+}
+
+fn run_clear() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = audit::denied_commands();
+    if entries.is_empty() {
+        println!("{}", "No denied commands.".dimmed());
+        return Ok(());
+    }
+    verify_user(&entries.iter().collect::<Vec<_>>(), "veto denied clear")?;
+
+    let cleared = audit::clear_denied_commands()?;
+    println!("{} {} denied command(s) cleared", "✓".green(), cleared);
+    Ok(())
+}
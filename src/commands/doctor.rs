@@ -1,8 +1,12 @@
 use colored::Colorize;
 
-use crate::auth::keyring::SecureKeyring;
-use crate::commands::{is_claude_configured, is_gemini_configured, is_cursor_configured};
-use crate::config::{get_config_dir, load_config};
+use veto::auth::keyring::SecureKeyring;
+use veto::auth::{TotpSettings, TotpState, TOTP_STEP_SECONDS};
+use crate::commands::{
+    installed_opencode_plugin_version, is_claude_configured, is_codex_configured, is_cursor_configured,
+    is_gemini_configured, OPENCODE_PLUGIN_VERSION,
+};
+use veto::config::{get_config_dir, load_config};
 
 pub fn run_doctor() {
     println!("{}", "Veto Doctor".bold());
@@ -114,6 +118,10 @@ pub fn run_doctor() {
         }
     }
 
+    if SecureKeyring::has_totp() {
+        check_totp_clock();
+    }
+
     // Claude Code integration
     println!();
     println!("{}", "Claude Code Integration:".bold());
@@ -137,6 +145,32 @@ pub fn run_doctor() {
         }
     }
 
+    // Codex CLI integration
+    println!();
+    println!("{}", "Codex CLI Integration:".bold());
+    let codex_home = std::env::var("CODEX_HOME")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".codex")));
+
+    print!("  hooks.json: ");
+    match codex_home.map(|d| d.join("hooks.json")) {
+        Some(path) if path.exists() => {
+            if is_codex_configured() {
+                println!("{} veto hooks configured", "✓".green());
+            } else {
+                println!("{} exists but no veto hooks (run `veto setup codex`)", "○".yellow());
+            }
+        }
+        Some(_) => {
+            println!("{} not found (optional)", "○".yellow());
+        }
+        None => {
+            println!("{} cannot determine path", "○".yellow());
+        }
+    }
+
     // Gemini CLI integration
     println!();
     println!("{}", "Gemini CLI Integration:".bold());
@@ -183,6 +217,28 @@ pub fn run_doctor() {
         }
     }
 
+    // OpenCode integration
+    println!();
+    println!("{}", "OpenCode Integration:".bold());
+    print!("  veto-gate.js: ");
+    match installed_opencode_plugin_version() {
+        Some(version) if version >= OPENCODE_PLUGIN_VERSION => {
+            println!("{} veto plugin v{} installed", "✓".green(), version);
+        }
+        Some(version) => {
+            let installed = if version == 0 { "unversioned".to_string() } else { format!("v{}", version) };
+            println!(
+                "{} veto plugin ({}) is outdated, v{} available (run `veto setup opencode`)",
+                "!".yellow(),
+                installed,
+                OPENCODE_PLUGIN_VERSION
+            );
+        }
+        None => {
+            println!("{} not installed (optional)", "○".yellow());
+        }
+    }
+
     // Summary
     println!();
     if all_ok {
@@ -191,3 +247,64 @@ pub fn run_doctor() {
         println!("{}", "Some checks failed. Run `veto init` to fix.".yellow());
     }
 }
+
+/// Report TOTP skew tolerance and clock drift, locally and against the
+/// network time
+fn check_totp_clock() {
+    let settings = TotpSettings::load();
+    let window = TOTP_STEP_SECONDS * settings.skew as u64;
+    println!();
+    println!("{}", "TOTP Clock:".bold());
+    println!("  Skew tolerance: ±{} step(s) (±{}s)", settings.skew, window);
+    println!(
+        "  Command binding: {}",
+        if settings.bind_command { "on".green() } else { "off".dimmed() }
+    );
+
+    print!("  Last code offset: ");
+    match TotpState::load() {
+        None => println!("{} state deleted or altered; codes already shown are refused", "✗".red()),
+        Some(state) if state.last_step == 0 => println!("{}", "no code used yet".dimmed()),
+        Some(state) => match state.last_offset {
+            0 => println!("{} in sync", "✓".green()),
+            offset => println!(
+                "{} authenticator {} by {} step(s); check the phone's clock",
+                "○".yellow(),
+                if offset < 0 { "behind" } else { "ahead" },
+                offset.abs()
+            ),
+        },
+    }
+
+    print!("  System clock: ");
+    match network_clock_offset() {
+        Some(offset) if offset.unsigned_abs() < TOTP_STEP_SECONDS / 2 => {
+            println!("{} within {}s of network time", "✓".green(), offset.abs())
+        }
+        Some(offset) if offset.unsigned_abs() <= window => println!(
+            "{} {}s {} network time; codes still accepted, but sync the clock",
+            "○".yellow(),
+            offset.abs(),
+            if offset < 0 { "behind" } else { "ahead of" }
+        ),
+        Some(offset) => println!(
+            "{} {}s {} network time; TOTP codes will be rejected",
+            "✗".red(),
+            offset.abs(),
+            if offset < 0 { "behind" } else { "ahead of" }
+        ),
+        None => println!("{}", "skipped (network time unavailable)".dimmed()),
+    }
+}
+
+/// Seconds the local clock is ahead of an HTTPS server's `Date` header
+fn network_clock_offset() -> Option<i64> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(3))
+        .build()
+        .ok()?;
+    let response = client.head("https://www.google.com").send().ok()?;
+    let date = response.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+    let remote = chrono::DateTime::parse_from_rfc2822(date).ok()?;
+    Some(chrono::Utc::now().timestamp() - remote.timestamp())
+}
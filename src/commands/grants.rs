@@ -0,0 +1,157 @@
+//! Grants subcommands for listing, adding and revoking approval grants
+
+use clap::Subcommand;
+use colored::Colorize;
+
+use veto::auth::grants::{self, Grant, GrantPolicy, GrantScope};
+use veto::config::loader::load_config;
+use veto::pipeline;
+use veto::rules::RiskLevel;
+
+#[derive(Subcommand)]
+pub enum GrantsCommands {
+    /// List active grants (default)
+    List,
+
+    /// Grant commands matching a pattern without prompting (requires auth)
+    Add {
+        /// Command pattern, e.g. "cargo publish*"
+        pattern: String,
+
+        /// Highest risk level covered: low, medium, high or critical
+        #[arg(long, default_value = "high")]
+        level: String,
+
+        /// Minutes the grant lasts (default from [auth.grants], 15)
+        #[arg(long)]
+        ttl: Option<u64>,
+
+        /// Commands allowed before the grant is used up (default from [auth.grants], 10)
+        #[arg(long)]
+        uses: Option<u32>,
+
+        /// Directory the grant applies in (default: current directory)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// Limit the grant to one agent session
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Revoke a grant
+    Revoke {
+        /// Grant id (see 'veto grants list')
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+
+        /// Revoke every grant
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+    },
+}
+
+pub fn run_grants_command(cmd: Option<GrantsCommands>) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd.unwrap_or(GrantsCommands::List) {
+        GrantsCommands::List => run_list(),
+        GrantsCommands::Add { pattern, level, ttl, uses, cwd, session } => {
+            run_add(&pattern, &level, ttl, uses, cwd, session)
+        }
+        GrantsCommands::Revoke { id, all } => run_revoke(id.as_deref(), all),
+    }
+}
+
+fn run_list() -> Result<(), Box<dyn std::error::Error>> {
+    let grants = grants::list();
+    if grants.is_empty() {
+        println!("{}", "No active grants.".dimmed());
+        return Ok(());
+    }
+
+    println!("{}", "Active grants:".bold());
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    for grant in &grants {
+        print_grant(grant, now);
+    }
+    Ok(())
+}
+
+fn print_grant(grant: &Grant, now: u64) {
+    let minutes = grant.expires_at.saturating_sub(now).div_ceil(60);
+    println!(
+        "  {} {} up to {} ({} min, {} of {} uses left)",
+        grant.id.cyan(),
+        grant.pattern.bold(),
+        grant.level,
+        minutes,
+        grant.remaining_uses(),
+        grant.max_uses
+    );
+    println!("    {} {}", "in".dimmed(), grant.cwd.as_deref().unwrap_or("any directory"));
+    if let Some(session) = &grant.session_id {
+        println!("    {} {}", "session".dimmed(), session);
+    }
+}
+
+fn run_add(
+    pattern: &str,
+    level: &str,
+    ttl: Option<u64>,
+    uses: Option<u32>,
+    cwd: Option<String>,
+    session: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let level = match level.to_lowercase().as_str() {
+        "low" => RiskLevel::Low,
+        "medium" => RiskLevel::Medium,
+        "high" => RiskLevel::High,
+        "critical" => RiskLevel::Critical,
+        other => return Err(format!("Unknown level '{}'. Use low, medium, high or critical.", other).into()),
+    };
+
+    let config = load_config()?;
+    let policy = GrantPolicy::from_config(&config);
+    if !policy.allows_level(level) {
+        return Err("Grants cannot cover CRITICAL commands unless [auth.grants] allow_critical = true.".into());
+    }
+
+    // Granting a level takes the same verification as running a command at it
+    println!("{}", "Adding grant".cyan().bold());
+    let factors = pipeline::auth_methods(&config, level, None);
+    if !factors.is_empty() {
+        pipeline::run_auth_chain(&config, &factors, &format!("veto grants add {}", pattern), None)?;
+    }
+
+    let mut scope = GrantScope::from_context(None);
+    if cwd.is_some() {
+        scope.cwd = cwd;
+    }
+    scope.session_id = session;
+    let grant = grants::add(
+        pattern,
+        level,
+        &scope,
+        ttl.unwrap_or(policy.ttl_minutes),
+        uses.unwrap_or(policy.max_uses),
+    )?;
+
+    println!("{}", "✓ Grant added".green());
+    print_grant(&grant, grant.created_at);
+    Ok(())
+}
+
+fn run_revoke(id: Option<&str>, all: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if all {
+        let revoked = grants::revoke_all()?;
+        println!("{} {} grant(s) revoked", "✓".green(), revoked);
+        return Ok(());
+    }
+
+    let id = id.ok_or("Grant id required (or --all)")?;
+    if grants::revoke(id)? {
+        println!("{} Grant {} revoked", "✓".green(), id);
+        Ok(())
+    } else {
+        Err(format!("No active grant '{}'", id).into())
+    }
+}
@@ -3,9 +3,9 @@ use std::path::PathBuf;
 use colored::Colorize;
 use dialoguer::Confirm;
 
-use crate::config::get_config_dir;
+use veto::config::get_config_dir;
 use crate::commands::{run_auth_command, AuthCommands};
-use crate::auth::keyring::SecureKeyring;
+use veto::auth::keyring::SecureKeyring;
 
 const DEFAULT_CONFIG_MACOS: &str = r#"# Veto Configuration
 # AI operation guardian - verify before execute
@@ -129,6 +129,27 @@ commands = [
 # category = "custom-medium"
 # patterns = ["moderate-command*"]
 # reason = "Custom medium-risk operation"
+
+# Network egress policy for curl, wget, ssh, scp and nc
+# [network]
+# allow_domains = ["github.com", "registry.npmjs.org"]
+# deny_domains = ["pastebin.com"]
+# secret_paths = ["~/work/secrets/*"]
+
+# Additive scoring: the matched rule and command signals are summed and
+# the level comes from the thresholds (a rule can set its own `score`)
+# [scoring]
+# enabled = true
+# production_keywords = ["prod", "production", "live"]
+# [scoring.weights]
+# sudo = 20
+# force = 15
+# production = 25
+# [scoring.thresholds]
+# low = 10
+# medium = 30
+# high = 55
+# critical = 90
 "#;
 
 pub fn run_init(force: bool) -> Result<(), Box<dyn std::error::Error>> {
@@ -6,7 +6,7 @@ use std::time::Duration;
 
 use colored::Colorize;
 
-use crate::audit::{clear_audit_log, get_audit_log_path, read_audit_log};
+use veto::audit::{clear_audit_log, get_audit_log_path, read_audit_log};
 use crate::cli::LogArgs;
 
 /// Run the log command
@@ -0,0 +1,399 @@
+//! `veto mcp` - Model Context Protocol server over stdio
+//!
+//! Exposes veto to MCP-capable agents as three tools:
+//! - `check_command`: risk level and required auth
+//! - `explain_risk`: matched rule, auth, and score breakdown
+//! - `run_command`: execute through veto's gate (auth, audit, deny cache)
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. stdout carries the
+//! protocol, so nothing here prompts on the terminal; verification happens
+//! through dialog/Touch ID/Telegram or codes the agent relays.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+use veto::auth::AuthContext;
+use veto::decision::{authorize, evaluate, Credentials, Decision, EvalContext, Outcome};
+use veto::executor::ShellExecutor;
+use veto::hooks::{self, HookAdapter, HookInput, HookResponse};
+use veto::rules::redact_secrets;
+
+/// Protocol version used when the client does not ask for one
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Captured output returned to the agent is truncated past this size
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Run the MCP server until stdin closes
+pub fn run_mcp() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = std::env::current_dir()?.display().to_string();
+    let mut context = EvalContext::load()
+        .with_auth_context(Some(AuthContext::new().with_cwd(cwd).with_tool_name("veto mcp")))
+        .hook(true);
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(&mut context, &line) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle one JSON-RPC message; notifications get no response
+fn handle_line(context: &mut EvalContext, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, -32700, &format!("Parse error: {}", e))),
+    };
+    let id = message.get("id").cloned()?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match message["method"].as_str().unwrap_or("") {
+        "initialize" => Ok(json!({
+            "protocolVersion": params["protocolVersion"].as_str().unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "veto", "version": env!("CARGO_PKG_VERSION") }
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(context, &params),
+        method => Err((-32601, format!("Method not found: {}", method))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tool_definitions() -> Value {
+    let command = json!({ "type": "string", "description": "Shell command" });
+    json!([
+        {
+            "name": "check_command",
+            "description": "Check a shell command's risk level and the verification it needs, without running it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "command": command },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "explain_risk",
+            "description": "Explain why a shell command is risky: matched rule, pattern, required auth and score breakdown.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "command": command },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "run_command",
+            "description": "Run a shell command through veto. Risky commands need user verification; if the result asks for a code, ask the user and call again with it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "command": command,
+                    "cwd": { "type": "string", "description": "Working directory" },
+                    "pin": { "type": "string", "description": "PIN provided by the user" },
+                    "totp": { "type": "string", "description": "TOTP code provided by the user" },
+                    "recovery": { "type": "string", "description": "Recovery code provided by the user" },
+                    "confirm": { "type": "boolean", "description": "The user explicitly approved this command" },
+                    "response": { "type": "string", "description": "Challenge response provided by the user" }
+                },
+                "required": ["command"]
+            }
+        }
+    ])
+}
+
+fn call_tool(context: &mut EvalContext, params: &Value) -> Result<Value, (i64, String)> {
+    let name = params["name"].as_str().unwrap_or("");
+    let args = &params["arguments"];
+    let command = args["command"]
+        .as_str()
+        .filter(|c| !c.trim().is_empty())
+        .ok_or((-32602, "Missing required argument: command".to_string()));
+
+    match name {
+        "check_command" => Ok(check_command(&evaluate(command?, context))),
+        "explain_risk" => Ok(explain_risk(context, &evaluate(command?, context))),
+        "run_command" => Ok(run_command(context, command?, args)),
+        _ => Err((-32602, format!("Unknown tool: {}", name))),
+    }
+}
+
+fn tool_result(text: String, structured: Option<Value>, is_error: bool) -> Value {
+    let mut result = json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error
+    });
+    if let Some(structured) = structured {
+        result["structuredContent"] = structured;
+    }
+    result
+}
+
+fn summary(decision: &Decision) -> Value {
+    json!({
+        "level": decision.level(),
+        "category": decision.risk.category,
+        "reason": decision.risk.reason,
+        "auth_methods": decision.auth_methods.iter().map(ToString::to_string).collect::<Vec<_>>(),
+    })
+}
+
+fn check_command(decision: &Decision) -> Value {
+    let mut text = format!("Risk: {}", decision.level());
+    if let Some(reason) = &decision.risk.reason {
+        text.push_str(&format!("\nReason: {}", reason));
+    }
+    if !decision.auth_methods.is_empty() {
+        text.push_str(&format!("\nVerification: {}", decision.describe_auth()));
+    }
+    tool_result(text, Some(summary(decision)), false)
+}
+
+fn explain_risk(context: &EvalContext, decision: &Decision) -> Value {
+    let risk = &decision.risk;
+    let mut lines = vec![format!("Command: {}", redact_secrets(&decision.eval_command))];
+    lines.push(format!("Risk: {}", decision.level()));
+    if let Some(category) = &risk.category {
+        lines.push(format!("Category: {}", category));
+    }
+    if let Some(reason) = &risk.reason {
+        lines.push(format!("Reason: {}", reason));
+    }
+    if let Some(pattern) = &risk.matched_pattern {
+        lines.push(format!("Pattern: {}", pattern));
+    }
+    if decision.auth_methods.is_empty() {
+        lines.push("Verification: none".to_string());
+    } else {
+        lines.push(format!("Verification: {}", decision.describe_auth()));
+    }
+    if decision.requires_challenge {
+        lines.push("Challenge: a one-time code is sent to the user".to_string());
+    }
+    if decision.previously_denied {
+        lines.push("Previously rejected by the user".to_string());
+    }
+
+    let mut structured = summary(decision);
+    structured["pattern"] = json!(risk.matched_pattern);
+    if context.engine.scoring_enabled() {
+        let breakdown = context.engine.explain_score(&decision.eval_command, context.cwd());
+        lines.push(format!("Score: {}", breakdown.total));
+        for signal in &breakdown.signals {
+            lines.push(format!("  +{} {}{}", signal.weight, signal.name, signal.detail.as_ref().map(|d| format!(" ({})", d)).unwrap_or_default()));
+        }
+        structured["score"] = json!(breakdown.total);
+    }
+    tool_result(lines.join("\n"), Some(structured), false)
+}
+
+fn run_command(context: &mut EvalContext, command: &str, args: &Value) -> Value {
+    let cwd = match command_cwd(context, args) {
+        Ok(cwd) => cwd,
+        Err(e) => return tool_result(e, None, true),
+    };
+    // Evaluate, bind challenges and scope grants to the directory the
+    // command runs in, not the server's
+    let call_auth = context.auth.clone().unwrap_or_default().with_cwd(cwd.display().to_string());
+    let server_auth = context.auth.replace(call_auth);
+    let decision = evaluate(command, context);
+    context.auth = server_auth;
+
+    // Codes come from the tool arguments or a VETO_* prefix on the command,
+    // never from the server's own environment
+    let arg = |name: &str| args[name].as_str().map(String::from);
+    let prefix = |name: &str| decision.env.get(name).cloned();
+    let credentials = Credentials {
+        pin: arg("pin").or_else(|| prefix("VETO_PIN")),
+        totp: arg("totp").or_else(|| prefix("VETO_TOTP")),
+        recovery: arg("recovery").or_else(|| prefix("VETO_RECOVERY")),
+        confirm: args["confirm"]
+            .as_bool()
+            .or_else(|| prefix("VETO_CONFIRM").map(|v| matches!(v.to_lowercase().as_str(), "yes" | "true" | "1"))),
+        response: arg("response").or_else(|| prefix("VETO_RESPONSE")),
+    };
+
+    let outcome = authorize(&decision, &credentials);
+    if !matches!(outcome, Outcome::Allowed { .. }) {
+        let response = hooks::render(&McpAdapter, &decision, outcome);
+        let text = response.stderr.or(response.stdout).unwrap_or_default();
+        return tool_result(text, Some(summary(&decision)), true);
+    }
+
+    // Pass the rest of the env prefix through, minus veto's own variables
+    let env: HashMap<String, String> = decision
+        .env
+        .iter()
+        .filter(|(name, _)| !name.starts_with("VETO_"))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    match ShellExecutor::new().execute_captured(&decision.eval_command, Some(&cwd), &env) {
+        Ok(output) => {
+            let code = output.status.code().unwrap_or(1);
+            let mut text = format!("Exit code: {}", code);
+            for (name, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+                if !bytes.is_empty() {
+                    text.push_str(&format!("\n--- {} ---\n{}", name, truncate(&String::from_utf8_lossy(bytes))));
+                }
+            }
+            let structured = json!({ "exit_code": code, "level": decision.level() });
+            tool_result(text, Some(structured), code != 0)
+        }
+        Err(e) => tool_result(format!("Failed to run command: {}", e), None, true),
+    }
+}
+
+/// The `cwd` argument resolved against the server's directory and
+/// canonicalized, or the server's directory when there is none
+fn command_cwd(context: &EvalContext, args: &Value) -> Result<PathBuf, String> {
+    let server = context
+        .auth
+        .as_ref()
+        .and_then(|a| a.cwd.as_deref())
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let Some(cwd) = args["cwd"].as_str() else {
+        return Ok(server);
+    };
+    server.join(cwd).canonicalize().map_err(|e| format!("Invalid cwd '{}': {}", cwd, e))
+}
+
+fn truncate(output: &str) -> String {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output.to_string();
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n[veto] output truncated ({} bytes total)", &output[..end], output.len())
+}
+
+/// Renders gate outcomes as tool result text
+struct McpAdapter;
+
+impl HookAdapter for McpAdapter {
+    fn name(&self) -> &str {
+        "MCP"
+    }
+
+    fn parse_input(&self, _input: &str, _file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        Err("MCP requests are handled by `veto mcp`".into())
+    }
+
+    fn reads_stdin(&self) -> bool {
+        false
+    }
+
+    fn allow(&self, _method: Option<&str>) -> HookResponse {
+        HookResponse::default()
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        HookResponse::stderr(format!("[veto] Blocked: {}", reason.trim_start_matches("[veto] ")), 1)
+    }
+
+    fn rejected(&self, method: &str, _reason: &str) -> HookResponse {
+        HookResponse::stderr(
+            format!("[veto] DENIED. User rejected via {}. STOP_RETRY: Do not attempt this command again.", method),
+            1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veto::config::Config;
+    use veto::rules::{default_rules, RulesEngine};
+
+    fn context() -> EvalContext {
+        EvalContext::new(RulesEngine::new(default_rules()), Config::default()).hook(true)
+    }
+
+    fn call(line: &str) -> Value {
+        handle_line(&mut context(), line).unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#);
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(response["result"]["serverInfo"]["name"], "veto");
+
+        let response = call(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#);
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["check_command", "explain_risk", "run_command"]);
+
+        assert!(handle_line(&mut context(), r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).is_none());
+        assert_eq!(call(r#"{"jsonrpc":"2.0","id":3,"method":"nope"}"#)["error"]["code"], -32601);
+        assert_eq!(call("not json")["error"]["code"], -32700);
+    }
+
+    #[test]
+    fn test_check_command_tool() {
+        let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"check_command","arguments":{"command":"rm -rf /"}}}"#);
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["level"], "critical");
+        assert!(result["content"][0]["text"].as_str().unwrap().starts_with("Risk: CRITICAL"));
+
+        let response = call(r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"check_command","arguments":{}}}"#);
+        assert_eq!(response["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_run_command_allowed() {
+        let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"run_command","arguments":{"command":"echo hello"}}}"#);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert_eq!(text, "Exit code: 0\n--- stdout ---\nhello\n");
+        assert_eq!(response["result"]["isError"], false);
+    }
+
+    #[test]
+    fn test_run_command_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let server = dir.path().canonicalize().unwrap();
+        let mut server_context =
+            context().with_auth_context(Some(AuthContext::new().with_cwd(server.display().to_string())));
+
+        let args = json!({ "cwd": "sub" });
+        assert_eq!(command_cwd(&server_context, &args).unwrap(), server.join("sub"));
+        assert_eq!(command_cwd(&server_context, &json!({})).unwrap(), server);
+        assert!(command_cwd(&server_context, &json!({ "cwd": "missing" })).is_err());
+
+        let result = run_command(&mut server_context, "pwd", &args);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert_eq!(text, format!("Exit code: 0\n--- stdout ---\n{}\n", server.join("sub").display()));
+        assert_eq!(server_context.auth.unwrap().cwd, Some(server.display().to_string()));
+
+        let result = run_command(&mut context(), "pwd", &json!({ "cwd": "/nonexistent/veto" }));
+        assert_eq!(result["isError"], true);
+    }
+}
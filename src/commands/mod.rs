@@ -1,7 +1,10 @@
 mod init;
 mod doctor;
 mod auth;
+mod grants;
+mod denied;
 mod shell;
+mod mcp;
 mod setup;
 mod upgrade;
 mod log;
@@ -9,16 +12,24 @@ mod log;
 pub use init::*;
 pub use doctor::*;
 pub use auth::*;
+pub use grants::*;
+pub use denied::*;
 pub use shell::run_shell;
+pub use mcp::run_mcp;
 pub use setup::{
     run_setup_claude,
+    run_setup_codex,
     run_setup_gemini,
     run_setup_opencode,
     run_setup_cursor,
+    run_setup_status,
+    run_setup_refresh,
     is_claude_configured,
+    is_codex_configured,
     is_gemini_configured,
-    is_opencode_configured,
     is_cursor_configured,
+    installed_opencode_plugin_version,
+    OPENCODE_PLUGIN_VERSION,
 };
 pub use upgrade::run_upgrade;
 pub use log::run_log;
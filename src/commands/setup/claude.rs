@@ -0,0 +1,145 @@
+//! Claude Code (`~/.claude/settings.json`, PreToolUse)
+
+use std::path::PathBuf;
+
+use super::{entry_runs_veto, is_veto_command, project_root, prune_hooks, run, JsonIntegration};
+use crate::cli::{SetupArgs, SetupScope};
+
+pub(super) const CLAUDE: JsonIntegration = JsonIntegration {
+    name: "Claude Code",
+    command: "claude",
+    path: settings_path,
+    install,
+    uninstall,
+    installed,
+    location: "PreToolUse",
+};
+
+fn settings_path(scope: SetupScope) -> Option<PathBuf> {
+    let base = match scope {
+        SetupScope::User => dirs::home_dir()?,
+        SetupScope::Project => project_root()?,
+    };
+    Some(base.join(".claude").join("settings.json"))
+}
+
+/// Check if veto hooks are already configured in Claude Code
+pub fn is_claude_configured() -> bool {
+    CLAUDE.is_configured()
+}
+
+/// Setup Claude Code hooks integration
+pub fn run_setup_claude(args: &SetupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    run(&CLAUDE, args)
+}
+
+fn installed(settings: &serde_json::Value) -> bool {
+    settings["hooks"]["PreToolUse"]
+        .as_array()
+        .is_some_and(|hooks| hooks.iter().any(entry_runs_veto))
+}
+
+/// Whether an entry runs veto's file operation hook
+fn is_file_hook(entry: &serde_json::Value) -> bool {
+    entry["hooks"]
+        .as_array()
+        .is_some_and(|inner| {
+            inner.iter().any(|ih| {
+                ih["command"]
+                    .as_str()
+                    .is_some_and(|c| is_veto_command(c) && c.contains("--file-op"))
+            })
+        })
+}
+
+fn install(settings: &mut serde_json::Value) {
+    // Note: Claude Code uses seconds for timeout (not milliseconds)
+    let bash_hook = serde_json::json!({
+        "matcher": "Bash",
+        "hooks": [{
+            "type": "command",
+            "command": "veto gate --claude",
+            "timeout": 90
+        }]
+    });
+
+    // File operations: writes, edits and reads
+    let file_hook = serde_json::json!({
+        "matcher": "Write|Edit|MultiEdit|Read",
+        "hooks": [{
+            "type": "command",
+            "command": "veto gate --claude --file-op",
+            "timeout": 30
+        }]
+    });
+
+    if !settings["hooks"].is_object() {
+        settings["hooks"] = serde_json::json!({});
+    }
+    if !settings["hooks"]["PreToolUse"].is_array() {
+        settings["hooks"]["PreToolUse"] = serde_json::json!([]);
+    }
+    let Some(hooks) = settings["hooks"]["PreToolUse"].as_array_mut() else {
+        return;
+    };
+
+    let bash_hook_idx = hooks
+        .iter()
+        .position(|h| h["matcher"].as_str() == Some("Bash") && entry_runs_veto(h) && !is_file_hook(h));
+    match bash_hook_idx {
+        // Older installs had no timeout
+        Some(idx) if hooks[idx]["hooks"][0].get("timeout").is_none() => hooks[idx] = bash_hook,
+        Some(_) => {}
+        None => hooks.push(bash_hook),
+    }
+
+    // Widen the matcher of older installs (Write|Edit)
+    match hooks.iter().position(is_file_hook) {
+        Some(idx) => hooks[idx]["matcher"] = file_hook["matcher"].clone(),
+        None => hooks.push(file_hook),
+    }
+}
+
+fn uninstall(settings: &mut serde_json::Value) {
+    if let Some(hooks) = settings["hooks"]["PreToolUse"].as_array_mut() {
+        hooks.retain(|h| !entry_runs_veto(h));
+    }
+    prune_hooks(settings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_is_idempotent_and_keeps_other_hooks() {
+        let mut settings = serde_json::json!({
+            "model": "opus",
+            "hooks": {"PreToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "echo 'veto gate'"}]}]}
+        });
+        install(&mut settings);
+        let once = settings.clone();
+        install(&mut settings);
+        assert_eq!(settings, once);
+        assert_eq!(settings["hooks"]["PreToolUse"].as_array().unwrap().len(), 3);
+
+        uninstall(&mut settings);
+        assert_eq!(settings["hooks"]["PreToolUse"].as_array().unwrap().len(), 1);
+        assert_eq!(settings["model"], "opus");
+    }
+
+    #[test]
+    fn test_install_upgrades_file_matcher() {
+        let mut settings = serde_json::json!({"hooks": {"PreToolUse": [
+            {"matcher": "Bash", "hooks": [{"type": "command", "command": "veto gate --claude", "timeout": 90}]},
+            {"matcher": "Write|Edit", "hooks": [{"type": "command", "command": "veto gate --claude --file-op", "timeout": 30}]}
+        ]}});
+        install(&mut settings);
+        let hooks = settings["hooks"]["PreToolUse"].as_array().unwrap();
+        assert_eq!(hooks.len(), 2);
+        assert_eq!(hooks[1]["matcher"], "Write|Edit|MultiEdit|Read");
+
+        uninstall(&mut settings);
+        assert_eq!(settings, serde_json::json!({}));
+    }
+}
@@ -0,0 +1,85 @@
+//! Codex CLI (`$CODEX_HOME/hooks.json`, PreToolUse)
+
+use std::path::PathBuf;
+
+use super::{entry_runs_veto, prune_hooks, run, JsonIntegration};
+use crate::cli::{SetupArgs, SetupScope};
+
+pub(super) const CODEX: JsonIntegration = JsonIntegration {
+    name: "Codex CLI",
+    command: "codex",
+    path: hooks_path,
+    install,
+    uninstall,
+    installed,
+    location: "PreToolUse",
+};
+
+/// Get Codex CLI hooks path (`$CODEX_HOME/hooks.json`, default ~/.codex).
+/// Codex has no project-level hooks.
+fn hooks_path(scope: SetupScope) -> Option<PathBuf> {
+    if scope == SetupScope::Project {
+        return None;
+    }
+    let codex_home = match std::env::var("CODEX_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => dirs::home_dir()?.join(".codex"),
+    };
+    Some(codex_home.join("hooks.json"))
+}
+
+/// Check if veto hooks are already configured in Codex CLI
+pub fn is_codex_configured() -> bool {
+    CODEX.is_configured()
+}
+
+/// Setup Codex CLI hooks integration
+pub fn run_setup_codex(args: &SetupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    run(&CODEX, args)
+}
+
+fn installed(settings: &serde_json::Value) -> bool {
+    settings["hooks"]["PreToolUse"]
+        .as_array()
+        .is_some_and(|hooks| hooks.iter().any(entry_runs_veto))
+}
+
+fn install(settings: &mut serde_json::Value) {
+    if installed(settings) {
+        return;
+    }
+
+    // Shell tools pass argv arrays; apply_patch carries file edits
+    let shell_hook = serde_json::json!({
+        "matcher": "shell|exec_command|local_shell",
+        "hooks": [{
+            "type": "command",
+            "command": "veto gate --codex",
+            "timeout": 90
+        }]
+    });
+
+    let file_hook = serde_json::json!({
+        "matcher": "apply_patch",
+        "hooks": [{
+            "type": "command",
+            "command": "veto gate --codex --file-op",
+            "timeout": 30
+        }]
+    });
+
+    if !settings["hooks"].is_object() {
+        settings["hooks"] = serde_json::json!({});
+    }
+    match settings["hooks"]["PreToolUse"].as_array_mut() {
+        Some(hooks) => hooks.extend([shell_hook, file_hook]),
+        None => settings["hooks"]["PreToolUse"] = serde_json::json!([shell_hook, file_hook]),
+    }
+}
+
+fn uninstall(settings: &mut serde_json::Value) {
+    if let Some(hooks) = settings["hooks"]["PreToolUse"].as_array_mut() {
+        hooks.retain(|h| !entry_runs_veto(h));
+    }
+    prune_hooks(settings);
+}
@@ -0,0 +1,85 @@
+//! Cursor CLI (`~/.cursor/hooks.json`)
+
+use std::path::PathBuf;
+
+use super::{is_veto_command, project_root, prune_hooks, run, JsonIntegration};
+use crate::cli::{SetupArgs, SetupScope};
+
+pub(super) const CURSOR: JsonIntegration = JsonIntegration {
+    name: "Cursor CLI",
+    command: "cursor",
+    path: hooks_path,
+    install,
+    uninstall,
+    installed,
+    location: "beforeShellExecution, beforeReadFile and preToolUse",
+};
+
+/// Cursor CLI hook events veto installs: (event, matcher, command)
+const CURSOR_HOOKS: &[(&str, Option<&str>, &str)] = &[
+    ("beforeShellExecution", None, "veto gate --cursor"),
+    ("beforeReadFile", None, "veto gate --cursor --file-op"),
+    ("preToolUse", Some("Write|Edit|Delete"), "veto gate --cursor --file-op"),
+];
+
+fn hooks_path(scope: SetupScope) -> Option<PathBuf> {
+    let base = match scope {
+        SetupScope::User => dirs::home_dir()?,
+        SetupScope::Project => project_root()?,
+    };
+    Some(base.join(".cursor").join("hooks.json"))
+}
+
+/// Check if veto hooks are already configured in Cursor CLI
+pub fn is_cursor_configured() -> bool {
+    CURSOR.is_configured()
+}
+
+/// Setup Cursor CLI hooks integration
+pub fn run_setup_cursor(args: &SetupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    run(&CURSOR, args)
+}
+
+fn runs_veto(hook: &serde_json::Value) -> bool {
+    hook["command"].as_str().is_some_and(is_veto_command)
+}
+
+fn installed(settings: &serde_json::Value) -> bool {
+    settings["hooks"]["beforeShellExecution"]
+        .as_array()
+        .is_some_and(|hooks| hooks.iter().any(runs_veto))
+}
+
+fn install(settings: &mut serde_json::Value) {
+    if settings.get("version").is_none() {
+        settings["version"] = serde_json::json!(1);
+    }
+    if !settings["hooks"].is_object() {
+        settings["hooks"] = serde_json::json!({});
+    }
+
+    for (event, matcher, command) in CURSOR_HOOKS {
+        let mut veto_hook = serde_json::json!({ "command": command });
+        if let Some(matcher) = matcher {
+            veto_hook["matcher"] = serde_json::json!(matcher);
+        }
+
+        match settings["hooks"][*event].as_array_mut() {
+            Some(hooks) => {
+                if !hooks.iter().any(runs_veto) {
+                    hooks.push(veto_hook);
+                }
+            }
+            None => settings["hooks"][*event] = serde_json::json!([veto_hook]),
+        }
+    }
+}
+
+fn uninstall(settings: &mut serde_json::Value) {
+    for (event, _, _) in CURSOR_HOOKS {
+        if let Some(hooks) = settings["hooks"][*event].as_array_mut() {
+            hooks.retain(|h| !runs_veto(h));
+        }
+    }
+    prune_hooks(settings);
+}
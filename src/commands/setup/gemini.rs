@@ -0,0 +1,124 @@
+//! Gemini CLI (`~/.gemini/settings.json`, BeforeTool)
+
+use std::path::PathBuf;
+
+use super::{entry_runs_veto, is_veto_command, project_root, prune_hooks, run, JsonIntegration};
+use crate::cli::{SetupArgs, SetupScope};
+
+pub(super) const GEMINI: JsonIntegration = JsonIntegration {
+    name: "Gemini CLI",
+    command: "gemini",
+    path: settings_path,
+    install,
+    uninstall,
+    installed,
+    location: "BeforeTool",
+};
+
+fn settings_path(scope: SetupScope) -> Option<PathBuf> {
+    let base = match scope {
+        SetupScope::User => dirs::home_dir()?,
+        SetupScope::Project => project_root()?,
+    };
+    Some(base.join(".gemini").join("settings.json"))
+}
+
+/// Check if veto hooks are already configured in Gemini CLI
+pub fn is_gemini_configured() -> bool {
+    GEMINI.is_configured()
+}
+
+/// Setup Gemini CLI hooks integration
+pub fn run_setup_gemini(args: &SetupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    run(&GEMINI, args)
+}
+
+fn installed(settings: &serde_json::Value) -> bool {
+    settings["hooks"]["BeforeTool"]
+        .as_array()
+        .is_some_and(|hooks| hooks.iter().any(entry_runs_veto))
+}
+
+/// Whether an entry holds the named veto hook (or, for installs without
+/// names, a veto command with/without `--file-op`)
+fn has_hook(entry: &serde_json::Value, name: &str, file_op: bool) -> bool {
+    entry["hooks"].as_array().is_some_and(|inner| {
+        inner.iter().any(|ih| {
+            ih["name"].as_str() == Some(name)
+                || ih["command"]
+                    .as_str()
+                    .is_some_and(|c| is_veto_command(c) && c.contains("--file-op") == file_op)
+        })
+    })
+}
+
+fn install(settings: &mut serde_json::Value) {
+    let shell_hook = serde_json::json!({
+        "matcher": "run_shell_command",
+        "hooks": [{
+            "name": "veto-gate-shell",
+            "type": "command",
+            "command": "veto gate --gemini",
+            "timeout": 90000,
+            "description": "Security gate for shell commands"
+        }]
+    });
+
+    let file_hook = serde_json::json!({
+        "matcher": "write_file|edit_file|replace_in_file|read_file",
+        "hooks": [{
+            "name": "veto-gate-file",
+            "type": "command",
+            "command": "veto gate --gemini --file-op",
+            "timeout": 30000,
+            "description": "Security gate for file operations"
+        }]
+    });
+
+    if !settings["hooks"].is_object() {
+        settings["hooks"] = serde_json::json!({});
+    }
+    if !settings["hooks"]["BeforeTool"].is_array() {
+        settings["hooks"]["BeforeTool"] = serde_json::json!([]);
+    }
+    let Some(hooks) = settings["hooks"]["BeforeTool"].as_array_mut() else {
+        return;
+    };
+
+    if !hooks.iter().any(|h| has_hook(h, "veto-gate-shell", false)) {
+        hooks.push(shell_hook);
+    }
+
+    // Widen the matcher of older installs (writes and edits only)
+    match hooks.iter().position(|h| has_hook(h, "veto-gate-file", true)) {
+        Some(idx) => hooks[idx]["matcher"] = file_hook["matcher"].clone(),
+        None => hooks.push(file_hook),
+    }
+}
+
+fn uninstall(settings: &mut serde_json::Value) {
+    if let Some(hooks) = settings["hooks"]["BeforeTool"].as_array_mut() {
+        hooks.retain(|h| !entry_runs_veto(h));
+    }
+    prune_hooks(settings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_adds_missing_file_hook() {
+        let mut settings = serde_json::json!({"hooks": {"BeforeTool": [
+            {"matcher": "run_shell_command", "hooks": [{"name": "veto-gate-shell", "type": "command", "command": "veto gate --gemini"}]}
+        ]}});
+        install(&mut settings);
+        let hooks = settings["hooks"]["BeforeTool"].as_array().unwrap();
+        assert_eq!(hooks.len(), 2);
+        assert_eq!(hooks[1]["hooks"][0]["name"], "veto-gate-file");
+
+        let once = settings.clone();
+        install(&mut settings);
+        assert_eq!(settings, once);
+    }
+}
@@ -0,0 +1,407 @@
+//! `veto setup` - install veto hooks into AI tools
+//!
+//! Each tool's hook settings are edited through pure install/uninstall
+//! transforms on its settings JSON. `run` applies one to the file for the
+//! chosen scope, so dry runs (a diff of the change), backups and
+//! `veto setup status` work the same way for every tool.
+
+mod claude;
+mod codex;
+mod cursor;
+mod gemini;
+mod opencode;
+
+pub use claude::{is_claude_configured, run_setup_claude};
+pub use codex::{is_codex_configured, run_setup_codex};
+pub use cursor::{is_cursor_configured, run_setup_cursor};
+pub use gemini::{is_gemini_configured, run_setup_gemini};
+pub use opencode::{installed_opencode_plugin_version, run_setup_opencode, OPENCODE_PLUGIN_VERSION};
+
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{SetupArgs, SetupScope};
+
+/// A tool whose hooks live in a JSON settings file
+struct JsonIntegration {
+    /// Tool name for messages (e.g. "Claude Code")
+    name: &'static str,
+    /// `veto setup <command>`
+    command: &'static str,
+    /// Settings file for a scope, or `None` if the tool has no such scope
+    path: fn(SetupScope) -> Option<PathBuf>,
+    /// Add or update veto's hooks
+    install: fn(&mut serde_json::Value),
+    /// Remove veto's hooks and nothing else
+    uninstall: fn(&mut serde_json::Value),
+    /// Whether any veto hook is present
+    installed: fn(&serde_json::Value) -> bool,
+    /// Where the hooks go, for the success message
+    location: &'static str,
+}
+
+impl JsonIntegration {
+    fn settings_path(&self, scope: SetupScope) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        match (self.path)(scope) {
+            Some(path) => Ok(path),
+            None if scope == SetupScope::Project => {
+                Err(format!("{} does not support --scope project", self.name).into())
+            }
+            None => Err("Cannot find home directory".into()),
+        }
+    }
+
+    /// Whether veto's hooks are present in the user scope settings
+    fn is_configured(&self) -> bool {
+        (self.path)(SetupScope::User)
+            .and_then(|path| read_settings(&path).ok())
+            .is_some_and(|(_, settings)| (self.installed)(&settings))
+    }
+
+    fn status(&self, scope: SetupScope) -> Option<(PathBuf, HookStatus)> {
+        let path = (self.path)(scope)?;
+        let status = match read_settings(&path) {
+            Ok((None, _)) => HookStatus::Missing,
+            Ok((Some(_), settings)) if !(self.installed)(&settings) => HookStatus::Missing,
+            Ok((Some(_), settings)) => {
+                let mut updated = settings.clone();
+                (self.install)(&mut updated);
+                if updated == settings {
+                    HookStatus::Current
+                } else {
+                    HookStatus::Outdated
+                }
+            }
+            Err(e) => HookStatus::Invalid(e.to_string()),
+        };
+        Some((path, status))
+    }
+}
+
+/// Install or remove an integration according to the setup flags
+fn run(integration: &JsonIntegration, args: &SetupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let path = integration.settings_path(args.scope)?;
+
+    println!("{}", format!("Setting up {} integration...", integration.name).bold());
+    println!();
+
+    let (original, settings) = read_settings(&path)?;
+    if args.uninstall && original.is_none() {
+        println!("  {} No {} settings found", "○".yellow(), integration.name);
+        return Ok(());
+    }
+
+    let mut updated = settings.clone();
+    if args.uninstall {
+        (integration.uninstall)(&mut updated);
+    } else {
+        (integration.install)(&mut updated);
+    }
+
+    if updated == settings {
+        if args.uninstall {
+            println!("  {} No veto hooks found", "○".yellow());
+        } else {
+            println!("  {} veto hooks already configured", "○".yellow());
+        }
+        return Ok(());
+    }
+
+    let content = serde_json::to_string_pretty(&updated)?;
+    if args.dry_run {
+        print_diff(&path, original.as_deref().unwrap_or(""), &content);
+        println!();
+        println!("Dry run: {} was not modified.", path.display());
+        return Ok(());
+    }
+
+    write_file(&path, &content)?;
+    if args.uninstall {
+        println!("  {} Removed veto hooks from {}", "✓".green(), integration.name);
+        println!();
+        println!("Restart {} for changes to take effect.", integration.name);
+    } else {
+        println!("  {} Added veto hooks to {} ({})", "✓".green(), integration.location, path.display());
+        println!();
+        println!("Done! Restart {} for changes to take effect.", integration.name);
+    }
+
+    Ok(())
+}
+
+/// Read a settings file: its original text (if it exists) and JSON value
+fn read_settings(path: &Path) -> Result<(Option<String>, serde_json::Value), Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok((None, serde_json::json!({})));
+    }
+    let content = fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok((Some(content), serde_json::json!({})));
+    }
+    let settings: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    if !settings.is_object() {
+        return Err(format!("{} is not a JSON object", path.display()).into());
+    }
+    Ok((Some(content), settings))
+}
+
+/// Write a file, backing up any existing version first
+fn write_file(path: &Path, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if path.exists() {
+        let backup = backup_file(path)?;
+        println!("  {} Backed up to {}", "✓".green(), backup.display());
+    } else if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Remove a file, backing it up first
+fn remove_file(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let backup = backup_file(path)?;
+    println!("  {} Backed up to {}", "✓".green(), backup.display());
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Copy a file to `<name>.veto-backup-<timestamp>` next to it
+fn backup_file(path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let name = path.file_name().ok_or("Invalid settings path")?.to_string_lossy();
+    let backup = path.with_file_name(format!("{}.veto-backup-{}", name, timestamp));
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// Print a unified diff of a settings change
+fn print_diff(path: &Path, old: &str, new: &str) {
+    println!("{}", format!("--- {}", path.display()).red());
+    println!("{}", format!("+++ {}", path.display()).green());
+    for line in unified_diff(old, new) {
+        if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Unified diff hunks (three lines of context) between two texts
+fn unified_diff(old: &str, new: &str) -> Vec<String> {
+    const CONTEXT: usize = 3;
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    // Edit script: (' ' | '-' | '+', old index, new index)
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', i, j));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j));
+            i += 1;
+        } else {
+            ops.push(('+', i, j));
+            j += 1;
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut k = 0;
+    while let Some(first) = ops[k..].iter().position(|op| op.0 != ' ').map(|p| p + k) {
+        // Extend the hunk while changes are within 2 * CONTEXT lines
+        let start = first.saturating_sub(CONTEXT);
+        let mut end = first;
+        let mut idx = first;
+        while idx < ops.len() {
+            if ops[idx].0 != ' ' {
+                end = idx;
+            } else if idx - end > 2 * CONTEXT {
+                break;
+            }
+            idx += 1;
+        }
+        let end = (end + CONTEXT + 1).min(ops.len());
+
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+        let old_start = if old_len == 0 { hunk[0].1 } else { hunk[0].1 + 1 };
+        let new_start = if new_len == 0 { hunk[0].2 } else { hunk[0].2 + 1 };
+        out.push(format!("@@ -{},{} +{},{} @@", old_start, old_len, new_start, new_len));
+        for &(kind, i, j) in hunk {
+            let line = if kind == '+' { b[j] } else { a[i] };
+            out.push(format!("{}{}", kind, line));
+        }
+        k = end;
+    }
+    out
+}
+
+/// Whether a hook command runs `veto gate` (`veto gate --claude`,
+/// `/usr/local/bin/veto gate ...`), as opposed to merely mentioning it
+fn is_veto_command(command: &str) -> bool {
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or("");
+    Path::new(program).file_name().is_some_and(|name| name == "veto") && words.next() == Some("gate")
+}
+
+/// Whether a `{"hooks": [{"command": ...}]}` entry runs veto
+fn entry_runs_veto(entry: &serde_json::Value) -> bool {
+    entry["hooks"]
+        .as_array()
+        .map(|inner| {
+            inner
+                .iter()
+                .any(|ih| ih["command"].as_str().is_some_and(is_veto_command))
+        })
+        .unwrap_or(false)
+}
+
+/// Remove empty `hooks.<event>` arrays and an empty `hooks` object
+fn prune_hooks(settings: &mut serde_json::Value) {
+    if let Some(hooks_obj) = settings["hooks"].as_object_mut() {
+        hooks_obj.retain(|_, v| v.as_array().is_none_or(|a| !a.is_empty()));
+        if hooks_obj.is_empty() {
+            if let Some(root) = settings.as_object_mut() {
+                root.remove("hooks");
+            }
+        }
+    }
+}
+
+/// Root of the current project: the nearest directory with `.git`, or
+/// the working directory
+fn project_root() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    let root = cwd.ancestors().find(|dir| dir.join(".git").exists()).unwrap_or(&cwd);
+    Some(root.to_path_buf())
+}
+
+/// Installed state of an integration
+#[derive(Debug, PartialEq, Eq)]
+enum HookStatus {
+    Current,
+    /// Installed by an older veto; `veto setup <tool>` updates it
+    Outdated,
+    Missing,
+    Invalid(String),
+}
+
+/// `veto setup status` - summarize every integration
+pub fn run_setup_status() {
+    println!("{}", "veto integrations".bold());
+    println!();
+
+    let integrations = [&claude::CLAUDE, &codex::CODEX, &gemini::GEMINI, &cursor::CURSOR];
+    for integration in integrations {
+        for (scope, label) in [(SetupScope::User, "user"), (SetupScope::Project, "project")] {
+            let Some((path, status)) = integration.status(scope) else {
+                continue;
+            };
+            // Only list project settings that exist
+            if scope == SetupScope::Project && !path.exists() {
+                continue;
+            }
+            print_status(&format!("{} ({})", integration.name, label), &path, &status, integration.command);
+        }
+    }
+
+    if let Some((path, status)) = opencode::status() {
+        let name = match installed_opencode_plugin_version() {
+            Some(0) => "OpenCode (unversioned)".to_string(),
+            Some(version) => format!("OpenCode (plugin v{})", version),
+            None => "OpenCode".to_string(),
+        };
+        print_status(&name, &path, &status, "opencode");
+    }
+}
+
+/// `veto setup refresh` - update outdated user-scope integrations
+pub fn run_setup_refresh() -> Result<(), Box<dyn std::error::Error>> {
+    let mut refreshed = 0;
+    for integration in [&claude::CLAUDE, &codex::CODEX, &gemini::GEMINI, &cursor::CURSOR] {
+        if let Some((_, HookStatus::Outdated)) = integration.status(SetupScope::User) {
+            run(integration, &SetupArgs::default())?;
+            println!();
+            refreshed += 1;
+        }
+    }
+    if let Some((_, HookStatus::Outdated)) = opencode::status() {
+        run_setup_opencode(&SetupArgs::default())?;
+        println!();
+        refreshed += 1;
+    }
+
+    if refreshed == 0 {
+        println!("{} All installed integrations are up to date", "✓".green());
+    }
+    Ok(())
+}
+
+fn print_status(name: &str, path: &Path, status: &HookStatus, command: &str) {
+    let state = match status {
+        HookStatus::Current => format!("{} installed", "✓".green()),
+        HookStatus::Outdated => format!("{} outdated (run `veto setup {}`)", "!".yellow(), command),
+        HookStatus::Missing => format!("{} not installed", "○".yellow()),
+        HookStatus::Invalid(e) => format!("{} {}", "✗".red(), e),
+    };
+    println!("  {:<24} {}", name, state);
+    println!("  {:<24} {}", "", path.display().to_string().dimmed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_veto_command() {
+        assert!(is_veto_command("veto gate --claude"));
+        assert!(is_veto_command("/usr/local/bin/veto gate --claude --file-op"));
+        assert!(!is_veto_command("echo 'veto gate'"));
+        assert!(!is_veto_command("my-veto gate"));
+        assert!(!is_veto_command("veto check"));
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(
+            unified_diff(old, new),
+            vec![
+                "@@ -2,9 +2,10 @@", " b", " c", " d", "-e", "+E", " f", " g", " h", " i", " j", "+k",
+            ]
+        );
+        assert_eq!(unified_diff("", "{}"), vec!["@@ -0,0 +1,1 @@", "+{}"]);
+        assert!(unified_diff("x\n", "x\n").is_empty());
+    }
+
+    #[test]
+    fn test_backup_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, "{}").unwrap();
+        let backup = backup_file(&path).unwrap();
+        assert!(backup.file_name().unwrap().to_string_lossy().starts_with("settings.json.veto-backup-"));
+        assert_eq!(fs::read_to_string(backup).unwrap(), "{}");
+    }
+}
@@ -0,0 +1,228 @@
+//! OpenCode (`~/.config/opencode/plugins/veto-gate.js`)
+
+use colored::Colorize;
+use std::path::PathBuf;
+
+use super::{print_diff, remove_file, write_file, HookStatus};
+use crate::cli::{SetupArgs, SetupScope};
+
+const OPENCODE_PLUGIN_FILENAME: &str = "veto-gate.js";
+
+/// Bump whenever `PLUGIN` changes; `veto setup opencode` upgrades
+/// installed plugins with an older version header
+pub const OPENCODE_PLUGIN_VERSION: u32 = 1;
+
+const VERSION_HEADER: &str = "// veto-gate plugin version: ";
+
+/// Plugin content - ES module format for OpenCode (uses Bun)
+/// Uses thrown errors to block tool execution and inform the AI when auth is needed
+const PLUGIN: &str = r#"export const VetoGate = async ({ project, client, $, directory, worktree }) => {
+  // Track denied commands across retries in the same session
+  const deniedCommands = new Set()
+
+  // Tools that veto should intercept
+  const INTERCEPTED_TOOLS = ["bash", "write", "edit", "read"]
+
+  const extractCommand = (input, output) => {
+    return (
+      output?.args?.command ??
+      input?.args?.command ??
+      output?.command ??
+      input?.command ??
+      null
+    )
+  }
+
+  const extractFilePath = (input, output) => {
+    return (
+      output?.args?.file_path ??
+      input?.args?.file_path ??
+      output?.args?.path ??
+      input?.args?.path ??
+      null
+    )
+  }
+
+  const normalizeCommand = (command) => (typeof command === "string" ? command.trim() : "")
+
+  return {
+    "tool.execute.before": async (input, output) => {
+      const tool = input.tool?.toLowerCase()
+      if (!INTERCEPTED_TOOLS.includes(tool)) return
+
+      let command
+      let isFileOp = false
+
+      if (tool === "bash") {
+        command = normalizeCommand(extractCommand(input, output))
+      } else {
+        // File operations: write, edit, read
+        const path = extractFilePath(input, output)
+        if (!path) return
+        command = `${tool}_file:${path}`
+        isFileOp = true
+      }
+
+      if (!command) return
+
+      // Block permanently denied commands
+      if (deniedCommands.has(command)) {
+        throw new Error("[veto] BLOCKED. This command was rejected. DO NOT RETRY.")
+      }
+
+      const args = isFileOp
+        ? ["gate", "--opencode", "--file-op", "--", command]
+        : ["gate", "--opencode", "--", command]
+
+      const result = await $`veto ${args}`.nothrow()
+
+      if (result.exitCode === 0) {
+        // Approved
+        return
+      }
+
+      // Check stderr for auth instructions
+      const stderr = result.stderr.toString()
+      const stdout = result.stdout.toString()
+      const message = (stderr || stdout).trim()
+
+      // If user explicitly denied (dialog/touchid), block permanently
+      if (message.includes("STOP_RETRY") || message.includes("User rejected")) {
+        deniedCommands.add(command)
+        throw new Error("[veto] BLOCKED. User rejected this command. DO NOT RETRY.")
+      }
+
+      // If auth code needed (PIN/TOTP), throw with instructions for AI
+      if (
+        message.includes("VETO_PIN=") ||
+        message.includes("VETO_TOTP=") ||
+        message.includes("VETO_CONFIRM=") ||
+        message.includes("VETO_RESPONSE=")
+      ) {
+        throw new Error(message)
+      }
+
+      // Other errors
+      throw new Error(`[veto] ${message || "Command blocked"}`)
+    }
+  }
+}
+"#;
+
+/// Get full path to veto plugin file
+fn get_opencode_plugin_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| {
+        h.join(".config")
+            .join("opencode")
+            .join("plugins")
+            .join(OPENCODE_PLUGIN_FILENAME)
+    })
+}
+
+/// Plugin file content with its version header
+fn plugin_content() -> String {
+    format!("{}{}\n{}", VERSION_HEADER, OPENCODE_PLUGIN_VERSION, PLUGIN)
+}
+
+/// Version from a plugin's header; 0 for plugins written before versioning
+fn plugin_version(content: &str) -> u32 {
+    content
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix(VERSION_HEADER))
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Version of the installed plugin, if any
+pub fn installed_opencode_plugin_version() -> Option<u32> {
+    let content = std::fs::read_to_string(get_opencode_plugin_file_path()?).ok()?;
+    Some(plugin_version(&content))
+}
+
+pub(super) fn status() -> Option<(PathBuf, HookStatus)> {
+    let path = get_opencode_plugin_file_path()?;
+    let status = match installed_opencode_plugin_version() {
+        None => HookStatus::Missing,
+        Some(version) if version < OPENCODE_PLUGIN_VERSION => HookStatus::Outdated,
+        Some(_) => HookStatus::Current,
+    };
+    Some((path, status))
+}
+
+/// Setup OpenCode plugin integration
+pub fn run_setup_opencode(args: &SetupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.scope == SetupScope::Project {
+        return Err("OpenCode does not support --scope project".into());
+    }
+    let plugin_file = get_opencode_plugin_file_path()
+        .ok_or("Cannot find home directory")?;
+
+    println!("{}", "Setting up OpenCode integration...".bold());
+    println!();
+
+    if args.uninstall {
+        if !plugin_file.exists() {
+            println!("  {} No veto plugin found", "○".yellow());
+            return Ok(());
+        }
+        if args.dry_run {
+            println!("Dry run: would remove {}", plugin_file.display());
+            return Ok(());
+        }
+        remove_file(&plugin_file)?;
+        println!("  {} Removed veto plugin from OpenCode", "✓".green());
+        println!();
+        println!("Restart OpenCode for changes to take effect.");
+        return Ok(());
+    }
+
+    let content = plugin_content();
+    let installed = std::fs::read_to_string(&plugin_file).ok();
+    let upgrade_from = match &installed {
+        Some(existing) if plugin_version(existing) >= OPENCODE_PLUGIN_VERSION => {
+            println!("  {} veto plugin already configured", "○".yellow());
+            return Ok(());
+        }
+        Some(existing) => {
+            let version = plugin_version(existing);
+            let from = if version == 0 { "unversioned".to_string() } else { format!("v{}", version) };
+            println!("  Upgrading veto plugin {} → v{}", from, OPENCODE_PLUGIN_VERSION);
+            print_diff(&plugin_file, existing, &content);
+            println!();
+            Some(from)
+        }
+        None => None,
+    };
+
+    if args.dry_run {
+        if upgrade_from.is_none() {
+            print_diff(&plugin_file, "", &content);
+            println!();
+        }
+        println!("Dry run: {} was not modified.", plugin_file.display());
+        return Ok(());
+    }
+
+    write_file(&plugin_file, &content)?;
+    match upgrade_from {
+        Some(from) => println!("  {} Upgraded veto plugin from {} to v{}", "✓".green(), from, OPENCODE_PLUGIN_VERSION),
+        None => println!("  {} Added veto plugin to OpenCode ({})", "✓".green(), plugin_file.display()),
+    }
+    println!();
+    println!("Done! Restart OpenCode for changes to take effect.");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_version_header() {
+        assert_eq!(plugin_version(&plugin_content()), OPENCODE_PLUGIN_VERSION);
+        assert_eq!(plugin_version(PLUGIN), 0);
+        assert_eq!(plugin_version("// veto-gate plugin version: 7\nexport ..."), 7);
+    }
+}
@@ -1,7 +1,8 @@
 //! Interactive shell wrapper with veto protection
 //!
 //! Every command is evaluated for risk and requires authentication
-//! based on the configured risk level mappings.
+//! based on the configured risk level mappings, through the same pipeline
+//! as `veto exec` and `veto gate`.
 
 use std::env;
 use std::path::PathBuf;
@@ -10,13 +11,11 @@ use colored::Colorize;
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 
-use crate::auth::{
-    Authenticator, ConfirmAuth, PinAuth, TotpAuth, TouchIdAuth, TelegramAuth,
-    AuthManager, manager::AsyncAuthBridge,
-};
-use crate::config::loader::load_config;
-use crate::executor::ShellExecutor;
-use crate::rules::{RulesEngine, RiskLevel, default_rules};
+use veto::auth::AuthContext;
+use veto::config::loader::{load_config, load_rules};
+use veto::executor::ShellExecutor;
+use veto::pipeline;
+use veto::rules::{self, RulesEngine, RiskLevel};
 
 /// History file location
 fn history_file() -> PathBuf {
@@ -44,7 +43,7 @@ pub fn run_shell() -> Result<(), Box<dyn std::error::Error>> {
     }
     let _ = rl.load_history(&history_path);
 
-    let engine = RulesEngine::new(default_rules());
+    let mut engine = RulesEngine::new(load_rules());
     let executor = ShellExecutor::new();
 
     loop {
@@ -69,6 +68,8 @@ pub fn run_shell() -> Result<(), Box<dyn std::error::Error>> {
 
                 // Handle built-in commands
                 if handle_builtin(command) {
+                    // `cd` may enter a project with its own .veto/rules.toml
+                    engine = RulesEngine::new(load_rules());
                     continue;
                 }
 
@@ -79,7 +80,8 @@ pub fn run_shell() -> Result<(), Box<dyn std::error::Error>> {
                 }
 
                 // Evaluate risk
-                let result = engine.evaluate(command);
+                let assessment = pipeline::assess(&engine, command, None);
+                let result = &assessment.result;
 
                 // Show risk level
                 let level_display = match result.level {
@@ -95,29 +97,17 @@ pub fn run_shell() -> Result<(), Box<dyn std::error::Error>> {
                     if let Some(reason) = &result.reason {
                         println!("{} {}", "Reason:".dimmed(), reason);
                     }
-                }
-
-                // Check if auth is required
-                let needs_auth = !matches!(result.level, RiskLevel::Allow);
 
-                if needs_auth {
-                    // Get auth methods for this risk level
-                    let methods = get_auth_methods(&result.level);
-
-                    // Run authentication
-                    match run_auth_chain(&methods, command) {
-                        Ok(()) => {
-                            // Authentication passed
-                        }
-                        Err(e) => {
-                            let msg = e.to_string();
-                            if msg.contains("cancelled") || msg.contains("Cancelled") {
-                                println!("{}", "Cancelled.".dimmed());
-                            } else {
-                                println!("{} {}", "Denied:".red(), e);
-                            }
-                            continue;
+                    let config = load_config().unwrap_or_default();
+                    let context = AuthContext::new().with_cwd(cwd.clone()).with_tool_name("veto shell");
+                    if let Err(e) = pipeline::authorize_interactive(&config, &assessment, None, Some(&context)) {
+                        let msg = e.to_string();
+                        if msg.contains("cancelled") || msg.contains("Cancelled") {
+                            println!("{}", "Cancelled.".dimmed());
+                        } else {
+                            println!("{} {}", "Denied:".red(), rules::redact_secrets(&msg));
                         }
+                        continue;
                     }
                 }
 
@@ -162,7 +152,7 @@ fn handle_builtin(command: &str) -> bool {
 
     match parts.first() {
         Some(&"cd") => {
-            let target = parts.get(1).map(|s| *s).unwrap_or("~");
+            let target = parts.get(1).copied().unwrap_or("~");
             let path = if target == "~" || target.starts_with("~/") {
                 dirs::home_dir()
                     .map(|home| {
@@ -218,85 +208,6 @@ fn shorten_path(path: &str) -> String {
     path.to_string()
 }
 
-/// Get authentication methods for a risk level
-fn get_auth_methods(level: &RiskLevel) -> Vec<String> {
-    let config = match load_config() {
-        Ok(c) => c,
-        Err(_) => return vec![crate::auth::default_auth_method().to_string()],
-    };
-
-    let auth_config = match config.auth {
-        Some(a) => a,
-        None => return vec![crate::auth::default_auth_method().to_string()],
-    };
-
-    let manager = AuthManager::new(auth_config);
-    manager.get_methods_for_level(&(*level).into())
-}
-
-/// Run authentication chain
-fn run_auth_chain(methods: &[String], command: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let config = load_config().unwrap_or_default();
-
-    for method in methods {
-        let authenticated = match method.as_str() {
-            "confirm" => {
-                let auth = ConfirmAuth::new();
-                auth.authenticate(command)?
-            }
-            "pin" => {
-                let auth = PinAuth::new();
-                if !auth.is_available() {
-                    return Err("PIN not configured".into());
-                }
-                auth.authenticate(command)?
-            }
-            "totp" => {
-                let auth = TotpAuth::new();
-                if !auth.is_available() {
-                    return Err("TOTP not configured".into());
-                }
-                auth.authenticate(command)?
-            }
-            "touchid" => {
-                let auth = TouchIdAuth::new();
-                if !auth.is_available() {
-                    return Err("Touch ID unavailable".into());
-                }
-                auth.authenticate(command)?
-            }
-            "telegram" => {
-                let chat_id = config
-                    .auth
-                    .as_ref()
-                    .and_then(|a| a.telegram.as_ref())
-                    .and_then(|t| t.chat_id.as_ref())
-                    .ok_or("Telegram not configured")?;
-
-                let timeout = config
-                    .auth
-                    .as_ref()
-                    .and_then(|a| a.telegram.as_ref())
-                    .and_then(|t| t.timeout_seconds)
-                    .unwrap_or(60);
-
-                let auth = TelegramAuth::new(chat_id).with_timeout(timeout as u64);
-                let bridge = AsyncAuthBridge::new(auth);
-                bridge.authenticate(command)?
-            }
-            _ => {
-                return Err(format!("Unknown auth method: {}", method).into());
-            }
-        };
-
-        if !authenticated {
-            return Err("Authentication cancelled".into());
-        }
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
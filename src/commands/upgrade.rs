@@ -5,7 +5,7 @@ use semver::Version;
 use std::env;
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 const GITHUB_API_URL: &str = "https://api.github.com/repos/runkids/veto/releases/latest";
@@ -108,7 +108,7 @@ fn can_write_to_exe(path: &PathBuf) -> bool {
 }
 
 /// Download a file to a temporary location
-fn download_file(url: &str, dest: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+fn download_file(url: &str, dest: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let status = Command::new("curl")
         .args(["-sL", "-o", dest.to_str().unwrap(), url])
         .status()?;
@@ -121,7 +121,7 @@ fn download_file(url: &str, dest: &PathBuf) -> Result<(), Box<dyn std::error::Er
 }
 
 /// Extract tarball to a directory
-fn extract_tarball(tarball: &PathBuf, dest_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+fn extract_tarball(tarball: &Path, dest_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let status = Command::new("tar")
         .args(["-xzf", tarball.to_str().unwrap(), "-C", dest_dir.to_str().unwrap()])
         .status()?;
@@ -266,5 +266,17 @@ pub fn run_upgrade(check_only: bool, force: bool) -> Result<(), Box<dyn std::err
     println!("{}", "✓ Successfully upgraded to version ".green().to_string() + &release.version.to_string().green().to_string() + "!");
     println!("{}", "  Run 'veto --version' to verify.".dimmed());
 
+    // The new binary knows the current hook versions
+    println!();
+    println!("{}", "Refreshing installed integrations...".cyan());
+    let refreshed = std::process::Command::new(&current_exe)
+        .args(["setup", "refresh"])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false);
+    if !refreshed {
+        println!("{}", "  Could not refresh integrations. Run 'veto setup refresh'.".yellow());
+    }
+
     Ok(())
 }
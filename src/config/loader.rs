@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 use super::Config;
@@ -37,30 +37,83 @@ pub fn load_config() -> Result<Config, ConfigError> {
     Ok(config)
 }
 
-/// Load rules from ~/.veto/rules.toml and merge with defaults
-/// User rules take priority (checked first)
+/// Find the project rules file: `.veto/rules.toml` in the current
+/// directory or the nearest parent that has one
+///
+/// The user config directory is skipped, so running from `~` does not load
+/// `~/.veto/rules.toml` twice.
+pub fn find_project_rules() -> Option<PathBuf> {
+    let config_dir = get_config_dir();
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(".veto"))
+        .filter(|dir| *dir != config_dir)
+        .map(|dir| dir.join("rules.toml"))
+        .find(|path| path.is_file())
+}
+
+fn parse_rules_file(path: &Path, content: &str) -> Option<Rules> {
+    match toml::from_str(content) {
+        Ok(r) => Some(r),
+        Err(e) => {
+            eprintln!("[veto] Warning: Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Load rules from ~/.veto/rules.toml and the project's .veto/rules.toml
+/// and merge them with defaults
+///
+/// Precedence is user rules, then project rules, then defaults. Project
+/// rules live in a repository an agent can write to, so they can only add
+/// restrictions: their whitelist, allowed domains and scoring settings are
+/// ignored.
 pub fn load_rules() -> Rules {
-    let rules_path = get_config_dir().join("rules.toml");
-    let defaults = default_rules();
+    let user_path = get_config_dir().join("rules.toml");
+    let user_content = std::fs::read_to_string(&user_path).ok();
+    let project_path = find_project_rules();
+    let project_content = project_path.as_ref().and_then(|p| std::fs::read_to_string(p).ok());
 
-    if !rules_path.exists() {
-        return defaults;
+    if user_content.is_none() && project_content.is_none() {
+        return default_rules();
     }
 
-    let content = match std::fs::read_to_string(&rules_path) {
-        Ok(c) => c,
-        Err(_) => return defaults,
+    let user_rules = user_content.as_deref().map(|c| parse_rules_file(&user_path, c));
+    let project_rules = match (&project_path, project_content.as_deref()) {
+        (Some(path), Some(content)) => Some(parse_rules_file(path, content)),
+        _ => None,
     };
 
-    let user_rules: Rules = match toml::from_str(&content) {
-        Ok(r) => r,
-        Err(e) => {
-            eprintln!("[veto] Warning: Failed to parse rules.toml: {}", e);
-            return defaults;
+    let mut rules = default_rules();
+    if let Some(project) = project_rules.flatten() {
+        if !project.whitelist.commands.is_empty() || !project.network.allow_domains.is_empty() {
+            eprintln!("[veto] Warning: Ignoring whitelist and allow_domains in project rules");
         }
-    };
+        rules = merge_rules(restrict_project_rules(project), rules);
+    }
+    if let Some(user) = user_rules.flatten() {
+        rules = merge_rules(user, rules);
+    }
+    rules
+}
+
+/// Drop the parts of project rules that could relax protection
+fn restrict_project_rules(project: Rules) -> Rules {
+    Rules {
+        whitelist: Default::default(),
+        network: crate::rules::NetworkRules {
+            allow_domains: vec![],
+            ..project.network
+        },
+        scoring: Default::default(),
+        ..project
+    }
+}
 
-    // Merge: user rules first (higher priority), then defaults
+/// Merge higher-priority rules over lower-priority ones: the first set's
+/// rules are checked first
+fn merge_rules(user_rules: Rules, defaults: Rules) -> Rules {
     Rules {
         critical: [user_rules.critical, defaults.critical].concat(),
         high: [user_rules.high, defaults.high].concat(),
@@ -70,6 +123,12 @@ pub fn load_rules() -> Rules {
             commands: [user_rules.whitelist.commands, defaults.whitelist.commands].concat(),
             paths: [user_rules.whitelist.paths, defaults.whitelist.paths].concat(),
         },
+        network: crate::rules::NetworkRules {
+            allow_domains: [user_rules.network.allow_domains, defaults.network.allow_domains].concat(),
+            deny_domains: [user_rules.network.deny_domains, defaults.network.deny_domains].concat(),
+            secret_paths: [user_rules.network.secret_paths, defaults.network.secret_paths].concat(),
+        },
+        scoring: user_rules.scoring,
     }
 }
 
@@ -115,6 +174,7 @@ pub fn update_telegram_config(chat_id: &str, timeout_seconds: Option<u32>) -> Re
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::AuthMethod;
 
     #[test]
     fn test_load_empty_config() {
@@ -139,4 +199,44 @@ prompt = "Verify operation"
         assert!(auth.touchid.is_some());
         assert!(auth.touchid.unwrap().enabled);
     }
+
+    #[test]
+    fn test_parse_auth_chains() {
+        let config: Config = toml::from_str(r#"
+[auth.levels]
+medium = "confirm"
+high = { any_of = ["touchid", "pin"] }
+critical = ["pin", { any_of = ["telegram", "totp"] }]
+"#).unwrap();
+        let levels = config.auth.unwrap().levels.unwrap();
+        assert_eq!(levels["medium"], AuthMethod::Single("confirm".to_string()));
+        assert_eq!(levels["high"].to_string(), "touchid|pin");
+        assert_eq!(levels["critical"].to_string(), "pin + telegram|totp");
+    }
+
+    #[test]
+    fn test_project_rules_cannot_relax_protection() {
+        let project: Rules = toml::from_str(r#"
+[[high]]
+category = "deploy"
+patterns = ["make deploy*"]
+
+[whitelist]
+commands = ["rm *"]
+
+[network]
+allow_domains = ["evil.example"]
+deny_domains = ["pastebin.com"]
+
+[scoring]
+enabled = true
+"#).unwrap();
+
+        let rules = merge_rules(restrict_project_rules(project), default_rules());
+        assert_eq!(rules.high[0].category, "deploy");
+        assert!(!rules.whitelist.commands.contains(&"rm *".to_string()));
+        assert!(rules.network.allow_domains.is_empty());
+        assert_eq!(rules.network.deny_domains, vec!["pastebin.com".to_string()]);
+        assert!(!rules.scoring.enabled);
+    }
 }
@@ -25,27 +25,106 @@ impl From<RulesRiskLevel> for RiskLevel {
     }
 }
 
-/// Authentication method - single method per risk level
-/// Arrays are accepted for backwards compatibility but only first element is used
+/// Authentication for a risk level
+///
+/// ```toml
+/// low = "confirm"
+/// high = { any_of = ["touchid", "pin"] }
+/// critical = ["pin", "telegram"]
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum AuthMethod {
     Single(String),
-    #[serde(skip_serializing)]
-    Multiple(Vec<String>),  // Deprecated: only first element used
+    /// Chain: every step is required, in order. Steps may be `any_of` groups.
+    Multiple(Vec<AuthMethod>),
+    /// Alternatives: any one method is enough
+    AnyOf { any_of: Vec<String> },
+}
+
+impl std::fmt::Display for AuthMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthMethod::Single(m) => write!(f, "{}", m),
+            AuthMethod::Multiple(steps) => {
+                let steps: Vec<String> = steps.iter().map(ToString::to_string).collect();
+                write!(f, "{}", steps.join(" + "))
+            }
+            AuthMethod::AnyOf { any_of } => write!(f, "{}", any_of.join("|")),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AuthConfig {
     pub default: Option<String>,
     pub levels: Option<HashMap<String, AuthMethod>>,
+    /// Method to use when one is unavailable or times out (`telegram = "totp"`)
     pub fallback: Option<HashMap<String, String>>,
+    /// Weakest method a fallback may resolve to (default `pin`)
+    pub fallback_floor: Option<String>,
+    pub lockout: Option<LockoutConfig>,
+    pub challenge: Option<ChallengeConfig>,
+    pub grants: Option<GrantsConfig>,
+    pub deny_cache: Option<DenyCacheConfig>,
+    pub credentials: Option<CredentialsConfig>,
     pub pin: Option<PinConfig>,
     pub touchid: Option<TouchIdConfig>,
     pub telegram: Option<TelegramConfig>,
     pub totp: Option<TotpConfig>,
 }
 
+/// Failed-attempt limits for PIN and TOTP codes
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LockoutConfig {
+    /// Failures before the method is locked until `veto auth unlock` (default 5)
+    pub max_attempts: Option<u32>,
+    /// Wait after the first failure, doubled on each further one (default 1)
+    pub backoff_seconds: Option<u64>,
+}
+
+/// Challenge codes for rules with `challenge = true`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ChallengeConfig {
+    /// Characters per code (default 6)
+    pub length: Option<usize>,
+    /// `digits` (default), `alphanumeric`, or the characters to use
+    pub alphabet: Option<String>,
+}
+
+/// Time-limited grants minted by approvals
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GrantsConfig {
+    /// Command patterns an approval mints a grant for (`cargo publish*`)
+    pub patterns: Option<Vec<String>>,
+    /// Minutes a grant lasts (default 15)
+    pub ttl_minutes: Option<u64>,
+    /// Commands a grant allows before it is used up (default 10)
+    pub max_uses: Option<u32>,
+    /// Let grants cover CRITICAL commands (default false)
+    pub allow_critical: Option<bool>,
+}
+
+const DEFAULT_DENY_TTL_MINUTES: u64 = 60;
+
+/// Blocking retries of commands the user rejected
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DenyCacheConfig {
+    /// Minutes a rejection blocks retries in the same session (default 60)
+    pub ttl_minutes: Option<u64>,
+}
+
+/// Which credentials hooks accept with a command
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CredentialsConfig {
+    /// Above LOW, codes only count as a challenge response (one-time and
+    /// bound to the command) and `VETO_CONFIRM` is ignored (default false)
+    pub hardened: Option<bool>,
+    /// Credentials honored per risk level, from `pin`, `totp`, `recovery`,
+    /// `confirm` and `response` (`high = ["totp", "response"]`)
+    pub accept: Option<HashMap<String, Vec<String>>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TouchIdConfig {
     pub enabled: bool,
@@ -69,9 +148,25 @@ pub struct PinConfig {
 pub struct TotpConfig {
     pub enabled: bool,
     pub issuer: Option<String>,
+    /// 30-second steps accepted on either side of the current one (default 1)
+    pub skew: Option<u8>,
+    /// A used code may be replayed only for the same command within its
+    /// window, instead of never
+    pub bind_command: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     pub auth: Option<AuthConfig>,
 }
+
+impl Config {
+    /// Minutes a rejection blocks retries, from `[auth.deny_cache]`
+    pub fn deny_ttl_minutes(&self) -> u64 {
+        self.auth
+            .as_ref()
+            .and_then(|a| a.deny_cache.as_ref())
+            .and_then(|d| d.ttl_minutes)
+            .unwrap_or(DEFAULT_DENY_TTL_MINUTES)
+    }
+}
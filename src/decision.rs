@@ -0,0 +1,885 @@
+//! Decision API
+//!
+//! `evaluate` turns a command into a `Decision` (risk, required auth
+//! methods, deny cache state) and `authorize` checks that decision against
+//! the credentials at hand and returns an `Outcome`. Neither prints hook
+//! output or exits the process: `veto gate` and other embedders decide how
+//! to present the outcome.
+//!
+//! ```no_run
+//! use veto::{authorize, evaluate, Credentials, EvalContext, Outcome};
+//!
+//! let context = EvalContext::load().hook(true);
+//! let decision = evaluate("git push --force origin main", &context);
+//! match authorize(&decision, &Credentials::from_env(&decision.env)) {
+//!     Outcome::Allowed { .. } => {}
+//!     other => eprintln!("blocked: {:?}", other),
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::audit;
+use crate::auth::grants::{self, Grant, GrantPolicy, GrantScope};
+use crate::auth::{
+    lockout, manager::AsyncAuthBridge, verify_response, AuthContext, AuthError, AuthFactor, Authenticator,
+    Challenge, ConfirmAuth, DialogAuth, PinAuth, RecoveryAuth, TelegramAuth, TotpAuth, TouchIdAuth, notify_challenge,
+    takes_response,
+};
+use crate::config::loader::{load_config, load_rules};
+use crate::config::Config;
+use crate::pipeline;
+use crate::rules::{RiskLevel, RiskResult, RulesEngine};
+
+/// Where a command comes from and what the caller can do with the outcome
+pub struct EvalContext {
+    pub engine: RulesEngine,
+    pub config: Config,
+    /// Details shown in dialog and Touch ID prompts
+    pub auth: Option<AuthContext>,
+    /// Use this auth method instead of the configured ones
+    pub auth_override: Option<String>,
+    /// Invoked by an agent hook: the deny cache and challenges apply and
+    /// nobody is at a terminal to confirm
+    pub hook: bool,
+    /// The agent can relay a code from the user and retry the command
+    pub can_retry: bool,
+}
+
+impl EvalContext {
+    pub fn new(engine: RulesEngine, config: Config) -> Self {
+        Self { engine, config, auth: None, auth_override: None, hook: false, can_retry: true }
+    }
+
+    /// Context with the user's rules and config
+    pub fn load() -> Self {
+        Self::new(RulesEngine::new(load_rules()), load_config().unwrap_or_default())
+    }
+
+    pub fn with_auth_context(mut self, auth: Option<AuthContext>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn with_auth_override(mut self, method: Option<String>) -> Self {
+        self.auth_override = method;
+        self
+    }
+
+    pub fn hook(mut self, hook: bool) -> Self {
+        self.hook = hook;
+        self
+    }
+
+    pub fn can_retry(mut self, can_retry: bool) -> Self {
+        self.can_retry = can_retry;
+        self
+    }
+
+    /// Where the command runs: the agent's working directory when known
+    pub fn cwd(&self) -> Option<&Path> {
+        self.auth.as_ref().and_then(|a| a.cwd.as_deref()).map(Path::new)
+    }
+}
+
+/// The policy decision for a command, before any credentials are checked
+#[derive(Debug, Clone)]
+pub struct Decision {
+    /// Command as received, including any `NAME=value` prefix
+    pub command: String,
+    /// Command with the env prefix stripped, as evaluated by the rules
+    pub eval_command: String,
+    /// Variables from the env prefix (`VETO_PIN=1234 cmd`)
+    pub env: HashMap<String, String>,
+    pub risk: RiskResult,
+    /// Auth chain for the risk level (every factor is required); empty
+    /// when the command is allowed
+    pub auth_methods: Vec<AuthFactor>,
+    /// The matched rule (or hardened mode) asks for a one-time challenge
+    /// code
+    pub requires_challenge: bool,
+    /// The user rejected this command in an earlier hook call of the same
+    /// session, and the denial has not expired
+    pub previously_denied: bool,
+    prompt: PromptContext,
+}
+
+/// The parts of the evaluation context `authorize` needs to prompt
+#[derive(Debug, Clone)]
+struct PromptContext {
+    config: Config,
+    auth: Option<AuthContext>,
+    hook: bool,
+    can_retry: bool,
+}
+
+impl Decision {
+    pub fn level(&self) -> RiskLevel {
+        self.risk.level
+    }
+
+    /// Whether the command passes without any verification
+    pub fn is_allowed(&self) -> bool {
+        self.auth_methods.is_empty()
+    }
+
+    /// The first auth method of the chain
+    pub fn primary_method(&self) -> Option<&str> {
+        self.auth_methods.first().and_then(|f| f.methods.first()).map(String::as_str)
+    }
+
+    /// The auth chain for display (`pin + touchid|telegram`)
+    pub fn describe_auth(&self) -> String {
+        let factors: Vec<String> = self.auth_methods.iter().map(ToString::to_string).collect();
+        factors.join(" + ")
+    }
+}
+
+/// Credentials supplied with a command
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    pub pin: Option<String>,
+    pub totp: Option<String>,
+    /// `VETO_RECOVERY`: one-time recovery code, accepted wherever TOTP is
+    pub recovery: Option<String>,
+    /// `VETO_CONFIRM`: `Some(false)` when set to anything but yes/true/1
+    pub confirm: Option<bool>,
+    /// `VETO_RESPONSE`: answer to a challenge (`<PIN><challenge>`,
+    /// `<TOTP><challenge>` or `<challenge>`)
+    pub response: Option<String>,
+}
+
+impl Credentials {
+    /// Read `VETO_*` credentials from the process environment, falling
+    /// back to the command's env prefix
+    pub fn from_env(prefix: &HashMap<String, String>) -> Self {
+        let var = |name: &str| std::env::var(name).ok().or_else(|| prefix.get(name).cloned());
+        Self {
+            pin: var("VETO_PIN"),
+            totp: var("VETO_TOTP"),
+            recovery: var("VETO_RECOVERY"),
+            confirm: var("VETO_CONFIRM").map(|v| is_truthy(&v)),
+            response: var("VETO_RESPONSE"),
+        }
+    }
+
+    /// Prefer an explicit PIN (e.g. `--pin`) over the environment
+    pub fn with_pin(mut self, pin: Option<String>) -> Self {
+        self.pin = pin.or(self.pin);
+        self
+    }
+
+    /// Prefer an explicit TOTP code (e.g. `--totp`) over the environment
+    pub fn with_totp(mut self, totp: Option<String>) -> Self {
+        self.totp = totp.or(self.totp);
+        self
+    }
+
+    /// Drop the credentials `policy` does not honor at `level`
+    pub fn accepted(mut self, policy: &CredentialPolicy, level: RiskLevel) -> Self {
+        let accepts = |kind: &str| policy.accepts(kind, level);
+        self.pin = self.pin.filter(|_| accepts("pin"));
+        self.totp = self.totp.filter(|_| accepts("totp"));
+        self.recovery = self.recovery.filter(|_| accepts("recovery"));
+        self.confirm = self.confirm.filter(|_| accepts("confirm"));
+        self.response = self.response.filter(|_| accepts("response"));
+        self
+    }
+
+    /// Whether a code for `method` came with the command
+    fn has(&self, method: &str, challenge: bool) -> bool {
+        let response = challenge && self.response.is_some();
+        match method {
+            "pin" => self.pin.is_some() || response,
+            "totp" => self.totp.is_some() || response,
+            "recovery" => self.recovery.is_some(),
+            "confirm" => self.confirm.is_some() || response,
+            _ => false,
+        }
+    }
+}
+
+/// Which credentials hooks honor, from `[auth.credentials]`
+///
+/// Hook credentials arrive in the command's `VETO_*` prefix, which the
+/// agent writes. In hardened mode, above LOW, a PIN or TOTP code only
+/// counts inside a challenge response (one-time, bound to the command,
+/// cwd and session), and `VETO_CONFIRM` and recovery codes are ignored
+/// unless `accept` lists them for the level.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CredentialPolicy {
+    pub hardened: bool,
+    /// Credentials honored per level (lowercase level name)
+    pub accept: HashMap<String, Vec<String>>,
+}
+
+impl CredentialPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        let credentials = config.auth.as_ref().and_then(|a| a.credentials.as_ref());
+        Self {
+            hardened: credentials.and_then(|c| c.hardened).unwrap_or(false),
+            accept: credentials.and_then(|c| c.accept.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Whether a `pin`, `totp`, `recovery`, `confirm` or `response`
+    /// credential counts at `level`
+    pub fn accepts(&self, kind: &str, level: RiskLevel) -> bool {
+        if let Some(kinds) = self.accept.get(&level.to_string().to_lowercase()) {
+            return kinds.iter().any(|k| k == kind);
+        }
+        !(self.binds_codes(level) && matches!(kind, "confirm" | "recovery"))
+    }
+
+    /// Whether codes at `level` have to come with a challenge
+    pub fn binds_codes(&self, level: RiskLevel) -> bool {
+        self.hardened && level > RiskLevel::Low
+    }
+}
+
+fn is_truthy(value: &str) -> bool {
+    value == "1" || value.eq_ignore_ascii_case("yes") || value.eq_ignore_ascii_case("true")
+}
+
+/// Result of authorizing a decision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The command may run; `method` is `None` when no verification was needed
+    Allowed { method: Option<String> },
+    /// Verification failed or the command was rejected earlier
+    Denied { reason: String },
+    /// The user rejected the command in a prompt (dialog, Touch ID, ...)
+    Rejected { method: String, reason: String },
+    /// PIN, TOTP codes or a confirmation have to come from the user, all
+    /// in one retry
+    CredentialsRequired { methods: Vec<String>, challenge: bool },
+    /// A challenge code was sent to the user; retry with `VETO_RESPONSE`
+    /// (plus codes for any other `methods`)
+    ChallengeIssued { methods: Vec<String> },
+    /// The host tool should ask the user itself (Cursor's permission prompt)
+    Ask,
+    /// The auth method cannot be used (not configured, unknown, ...)
+    Unavailable { message: String },
+}
+
+/// Evaluate a command under a context
+pub fn evaluate(command: &str, context: &EvalContext) -> Decision {
+    let assessment = pipeline::assess(&context.engine, command, context.cwd());
+    let level = assessment.result.level;
+    let auth_methods = pipeline::auth_methods(&context.config, level, context.auth_override.as_deref());
+    let session = context.auth.as_ref().and_then(|a| a.session_id.as_deref());
+    let binds_codes = CredentialPolicy::from_config(&context.config).binds_codes(level);
+    let previously_denied =
+        context.hook && !auth_methods.is_empty() && audit::was_denied_command(&assessment.eval_command, session);
+
+    Decision {
+        command: assessment.command,
+        eval_command: assessment.eval_command,
+        env: assessment.env,
+        requires_challenge: (assessment.result.challenge || binds_codes) && context.hook,
+        risk: assessment.result,
+        auth_methods,
+        previously_denied,
+        prompt: PromptContext {
+            config: context.config.clone(),
+            auth: context.auth.clone(),
+            hook: context.hook,
+            can_retry: context.can_retry,
+        },
+    }
+}
+
+/// Check a decision against credentials, prompting through dialog,
+/// Touch ID, Telegram or (outside hooks) the terminal when configured
+///
+/// Every factor of the chain has to pass. Codes (PIN, TOTP, confirmation,
+/// challenge response) are checked before anyone is prompted, and missing
+/// ones are asked for together so the agent can retry once.
+///
+/// A grant minted by an earlier approval (`[auth.grants]`) passes the
+/// command without asking; an approval may mint one.
+///
+/// Final outcomes (allowed, denied, rejected, ask) are written to the
+/// audit log; rejections in hooks also go to the deny cache.
+pub fn authorize(decision: &Decision, credentials: &Credentials) -> Outcome {
+    if decision.is_allowed() {
+        return Outcome::Allowed { method: None };
+    }
+
+    let outcome = if decision.previously_denied {
+        Outcome::Denied {
+            reason: "[veto] Previously rejected by the user in this session. Do not retry. If the user changes their mind, they can run 'veto denied remove <id>' in a terminal.".to_string(),
+        }
+    } else if let Some(grant) = redeem_grant(decision) {
+        Outcome::Allowed { method: Some(format!("grant {}", grant.id)) }
+    } else {
+        // Hook credentials come from the agent; honor only what the policy allows
+        let credentials = if decision.prompt.hook {
+            let policy = CredentialPolicy::from_config(&decision.prompt.config);
+            credentials.clone().accepted(&policy, decision.level())
+        } else {
+            credentials.clone()
+        };
+        let (outcome, methods) = authorize_chain(decision, &credentials);
+        if matches!(outcome, Outcome::Allowed { .. }) && methods.iter().any(|m| held_by_user(m)) {
+            mint_grant(decision);
+        }
+        outcome
+    };
+
+    record(decision, &outcome);
+    outcome
+}
+
+/// A grant from an earlier approval covering this command. Challenge
+/// rules always ask afresh.
+fn redeem_grant(decision: &Decision) -> Option<Grant> {
+    if decision.risk.challenge {
+        return None;
+    }
+    let policy = GrantPolicy::from_config(&decision.prompt.config);
+    let scope = GrantScope::from_context(decision.prompt.auth.as_ref());
+    grants::redeem(&decision.eval_command, decision.level(), &scope, &policy)
+}
+
+/// Whether approving with `method` shows the user was there: a code only
+/// they hold or a prompt only they can answer. `VETO_CONFIRM=yes` and a
+/// bare confirmation can come from the agent itself.
+fn held_by_user(method: &str) -> bool {
+    matches!(method, "pin" | "totp" | "recovery" | "touchid" | "dialog" | "telegram")
+}
+
+/// Let an approval stand for later runs when `[auth.grants]` lists a
+/// pattern the command matches. Only approvals with a factor the user
+/// holds may mint one.
+fn mint_grant(decision: &Decision) {
+    if decision.risk.challenge {
+        return;
+    }
+    let policy = GrantPolicy::from_config(&decision.prompt.config);
+    let scope = GrantScope::from_context(decision.prompt.auth.as_ref());
+    match grants::mint(&decision.eval_command, decision.level(), &scope, &policy) {
+        Ok(Some(grant)) => eprintln!(
+            "[veto] Granted '{}' for {} minutes ({} uses). Revoke with 'veto grants revoke {}'.",
+            grant.pattern, policy.ttl_minutes, grant.max_uses, grant.id
+        ),
+        Ok(None) => {}
+        Err(e) => eprintln!("[veto] Warning: Failed to save grant: {}", e),
+    }
+}
+
+/// How one factor of the chain is satisfied
+enum Step {
+    /// A code that came with the command
+    Code(String),
+    /// Ask the user through dialog, Touch ID, Telegram or the terminal
+    Prompt(String),
+    /// A code the agent has to ask the user for
+    Missing(String),
+}
+
+/// Run the chain, returning the outcome and the methods that approved
+fn authorize_chain(decision: &Decision, credentials: &Credentials) -> (Outcome, Vec<String>) {
+    let mut codes = Vec::new();
+    let mut prompts = Vec::new();
+    let mut missing = Vec::new();
+    // Methods given or asked for as codes, in chain order
+    let mut code_methods = Vec::new();
+    for factor in &decision.auth_methods {
+        match plan(decision, factor, credentials) {
+            Ok(Step::Code(method)) => {
+                code_methods.push(method.clone());
+                codes.push((factor, method));
+            }
+            Ok(Step::Prompt(method)) => prompts.push((factor, method)),
+            Ok(Step::Missing(method)) => {
+                code_methods.push(method.clone());
+                missing.push(method);
+            }
+            Err(outcome) => return (outcome, vec![]),
+        }
+    }
+    if let Some(outcome) = unbound_code(decision, credentials, &code_methods) {
+        return (outcome, vec![]);
+    }
+    if !missing.is_empty() {
+        return (request_credentials(decision, missing), vec![]);
+    }
+    // Codes alone do not pass a challenge rule: one of them has to come
+    // with the challenge
+    if decision.requires_challenge
+        && decision.prompt.can_retry
+        && credentials.response.is_none()
+        && codes.iter().any(|(_, method)| takes_response(method))
+    {
+        return (issue_challenge(decision, codes.into_iter().map(|(_, method)| method).collect()), vec![]);
+    }
+
+    let mut approved = Vec::new();
+    let mut methods = Vec::new();
+    let mut response = credentials.response.as_deref().filter(|_| decision.requires_challenge);
+    for (factor, method) in codes {
+        match verify_code(decision, credentials, &method, &mut response) {
+            Ok(label) => approved.push(pipeline::approved_label(factor, &method, &label)),
+            Err(outcome) => return (outcome, vec![]),
+        }
+        methods.push(method);
+    }
+    for (factor, method) in prompts {
+        match prompt_with_fallback(decision, factor, method) {
+            Ok((method, label)) => {
+                approved.push(label);
+                methods.push(method);
+            }
+            Err(outcome) => return (outcome, vec![]),
+        }
+    }
+
+    (Outcome::Allowed { method: Some(approved.join("+")) }, methods)
+}
+
+/// In hardened mode a PIN or TOTP code only counts inside a challenge
+/// response, and one response covers one factor. Refuse, before anything
+/// is verified, when a code would go unbound: a second code factor in the
+/// chain, or a tool that cannot relay a challenge response.
+fn unbound_code(decision: &Decision, credentials: &Credentials, methods: &[String]) -> Option<Outcome> {
+    let policy = CredentialPolicy::from_config(&decision.prompt.config);
+    if !decision.prompt.hook || !policy.binds_codes(decision.level()) {
+        return None;
+    }
+    let mut responding = methods.iter().filter(|m| takes_response(m));
+    if credentials.response.is_some() || decision.prompt.can_retry {
+        responding.next();
+    }
+    let method = responding.find(|m| matches!(m.as_str(), "pin" | "totp"))?;
+    Some(Outcome::Denied {
+        reason: format!(
+            "[veto] Hardened mode: {} codes only count inside a one-time challenge response, and one response covers one step. Approve with dialog, Touch ID or Telegram, or run the command in a terminal.",
+            method.to_uppercase()
+        ),
+    })
+}
+
+/// Decide how a factor is satisfied: a supplied code for any of its
+/// methods (or their fallbacks) wins, otherwise the first available
+/// method is used
+fn plan(decision: &Decision, factor: &AuthFactor, credentials: &Credentials) -> Result<Step, Outcome> {
+    let candidates = pipeline::candidates(&decision.prompt.config, factor);
+    if let Some(method) = candidates.into_iter().find(|m| credentials.has(m, decision.requires_challenge)) {
+        return Ok(Step::Code(method));
+    }
+    step_for(decision, pipeline::choose_method(&decision.prompt.config, factor))
+}
+
+/// How a method without a supplied code is satisfied
+fn step_for(decision: &Decision, method: String) -> Result<Step, Outcome> {
+    match method.as_str() {
+        "totp" | "pin" | "recovery" if lockout::is_locked(&method) => Err(Outcome::Denied {
+            reason: format!(
+                "[veto] {} locked after too many failed attempts. User must run 'veto auth unlock' in a terminal.",
+                method.to_uppercase()
+            ),
+        }),
+        "totp" if !TotpAuth::new().is_available() => Err(Outcome::Unavailable {
+            message: "[veto] TOTP not configured. User must run 'veto auth setup-totp' first to enable TOTP authentication.".to_string(),
+        }),
+        "pin" if !PinAuth::new().is_available() => Err(Outcome::Unavailable {
+            message: "[veto] PIN not configured. User must run 'veto auth set-pin' first to enable PIN authentication.".to_string(),
+        }),
+        "recovery" if !RecoveryAuth::new().is_available() => Err(Outcome::Unavailable {
+            message: "[veto] No recovery codes left. User must run 'veto auth recovery regenerate' in a terminal.".to_string(),
+        }),
+        "totp" | "pin" | "recovery" => Ok(Step::Missing(method)),
+        "confirm" if !decision.prompt.hook => Ok(Step::Prompt(method)),
+        "confirm" => Ok(Step::Missing(method)),
+        "dialog" | "touchid" | "telegram" => Ok(Step::Prompt(method)),
+        _ => Err(Outcome::Unavailable {
+            message: "No suitable auth method configured. Run 'veto auth setup-totp' or 'veto auth set-pin' first.".to_string(),
+        }),
+    }
+}
+
+/// Codes are missing: ask the user for all of them, sending a challenge
+/// first when the rule requires one
+fn request_credentials(decision: &Decision, methods: Vec<String>) -> Outcome {
+    let context = &decision.prompt;
+    let challenge_method = methods.iter().any(|m| takes_response(m));
+
+    if decision.requires_challenge && context.can_retry && challenge_method {
+        return issue_challenge(decision, methods);
+    }
+    // A lone confirmation can go to the tool's own permission prompt
+    if !context.can_retry && !decision.requires_challenge && decision.auth_methods.len() == 1 && methods == ["confirm"] {
+        return Outcome::Ask;
+    }
+    Outcome::CredentialsRequired { methods, challenge: decision.requires_challenge }
+}
+
+/// Prompt for a factor, moving along its fallbacks while methods are
+/// unavailable or time out. A fallback that needs a code is asked for.
+/// Returns the method that approved and its audit label.
+fn prompt_with_fallback(decision: &Decision, factor: &AuthFactor, method: String) -> Result<(String, String), Outcome> {
+    let mut method = method;
+    loop {
+        let failed = match prompt(decision, &method) {
+            Ok(Outcome::Allowed { method: Some(label) }) => {
+                let label = pipeline::approved_label(factor, &method, &label);
+                return Ok((method, label));
+            }
+            Ok(outcome) => return Err(outcome),
+            Err(outcome) => outcome,
+        };
+
+        let Some(next) = pipeline::next_fallback(&decision.prompt.config, factor, &method) else {
+            return Err(failed);
+        };
+        eprintln!("[veto] {} unavailable, falling back to {}", method, next);
+        match step_for(decision, next)? {
+            Step::Prompt(next) => method = next,
+            Step::Missing(next) | Step::Code(next) => return Err(request_credentials(decision, vec![next])),
+        }
+    }
+}
+
+/// Ask the user directly; `Err` when the method is unavailable or timed
+/// out, holding the outcome to report if there is no fallback
+///
+/// Under a challenge rule, dialog and Touch ID show the challenge (also
+/// sent as a notification, to cross-check) and Telegram approvals must
+/// echo it. An approval uses the challenge up.
+fn prompt(decision: &Decision, method: &str) -> Result<Outcome, Outcome> {
+    if !decision.requires_challenge || !matches!(method, "dialog" | "touchid" | "telegram") {
+        return prompt_with(decision, method, None);
+    }
+    let challenge = Challenge::generate(&decision.eval_command, decision.prompt.auth.as_ref())
+        .map_err(|e| Outcome::Unavailable { message: format!("[veto] Failed to generate challenge: {}", e) })?;
+    if method != "telegram" {
+        if let Err(e) = notify_challenge(&challenge, &decision.eval_command) {
+            eprintln!("[veto] Warning: Failed to send notification: {}", e);
+        }
+    }
+
+    let result = prompt_with(decision, method, Some(&challenge));
+    match result {
+        Ok(Outcome::Allowed { .. }) => {
+            if let Err(e) = challenge.consume() {
+                return Ok(Outcome::Denied { reason: format!("[veto] {}", e) });
+            }
+        }
+        _ => challenge.discard(),
+    }
+    result
+}
+
+fn prompt_with(decision: &Decision, method: &str, challenge: Option<&Challenge>) -> Result<Outcome, Outcome> {
+    let context = &decision.prompt;
+    let auth_context = match challenge {
+        Some(challenge) => Some(context.auth.clone().unwrap_or_default().with_challenge(&challenge.id)),
+        None => context.auth.clone(),
+    };
+    match method {
+        "confirm" => {
+            Ok(match ConfirmAuth::new().authenticate(&decision.command) {
+                Ok(true) => Outcome::Allowed { method: Some("confirmation".to_string()) },
+                _ => Outcome::Rejected {
+                    method: "confirmation".to_string(),
+                    reason: "User cancelled confirmation".to_string(),
+                },
+            })
+        }
+        "dialog" => {
+            let auth = DialogAuth::new();
+            let approved = match &auth_context {
+                Some(ctx) => auth.authenticate_with_context(&decision.command, ctx),
+                None => auth.authenticate(&decision.command),
+            };
+            prompt_outcome(approved, "dialog", "User cancelled via dialog")
+        }
+        "touchid" => {
+            let auth = TouchIdAuth::new();
+            let approved = match &auth_context {
+                Some(ctx) => auth.authenticate_with_context(&decision.command, ctx),
+                None => auth.authenticate(&decision.command),
+            };
+            prompt_outcome(approved, "Touch ID", "User cancelled via Touch ID")
+        }
+        _ => {
+            let telegram = context.config.auth.as_ref().and_then(|a| a.telegram.as_ref());
+            let Some(chat_id) = telegram.and_then(|t| t.chat_id.as_ref()) else {
+                return Err(Outcome::Unavailable {
+                    message: "Telegram not configured. Run 'veto auth setup-telegram' first.".to_string(),
+                });
+            };
+            let timeout = telegram.and_then(|t| t.timeout_seconds).unwrap_or(60);
+            let auth = TelegramAuth::new(chat_id)
+                .with_timeout(timeout as u64)
+                .with_challenge(challenge.map(|c| c.id.clone()));
+            let bridge = AsyncAuthBridge::new(auth);
+
+            eprintln!("📱 Telegram approval request sent. Waiting for response...");
+            prompt_outcome(bridge.authenticate(&decision.command), "Telegram", "User denied via Telegram")
+        }
+    }
+}
+
+fn prompt_outcome(approved: crate::auth::AuthResult, method: &str, reason: &str) -> Result<Outcome, Outcome> {
+    let rejected = Outcome::Rejected { method: method.to_string(), reason: reason.to_string() };
+    match approved {
+        Ok(true) => Ok(Outcome::Allowed { method: Some(method.to_string()) }),
+        Err(e) if pipeline::is_unavailable(&e) => Err(rejected),
+        _ => Ok(rejected),
+    }
+}
+
+/// Generate a challenge code and send it to the user out of band
+fn issue_challenge(decision: &Decision, methods: Vec<String>) -> Outcome {
+    match Challenge::generate(&decision.eval_command, decision.prompt.auth.as_ref()) {
+        Ok(challenge) => {
+            if let Err(e) = notify_challenge(&challenge, &decision.eval_command) {
+                eprintln!("[veto] Warning: Failed to send notification: {}", e);
+            }
+            Outcome::ChallengeIssued { methods }
+        }
+        Err(e) => Outcome::Unavailable { message: format!("[veto] Failed to generate challenge: {}", e) },
+    }
+}
+
+/// Verify one supplied code; the challenge response covers the first PIN,
+/// TOTP or confirmation factor
+fn verify_code(
+    decision: &Decision,
+    credentials: &Credentials,
+    method: &str,
+    response: &mut Option<&str>,
+) -> Result<String, Outcome> {
+    if takes_response(method) {
+        if let Some(response) = response.take() {
+            let verified = verify_response(response, &decision.eval_command, decision.prompt.auth.as_ref(), method);
+            return if verified.success {
+                Ok(verified.method)
+            } else {
+                Err(Outcome::Denied {
+                    reason: verified.error.unwrap_or_else(|| "Challenge verification failed".to_string()),
+                })
+            };
+        }
+    }
+
+    let (verified, label) = match method {
+        "totp" => (credentials.totp.as_deref().map(|code| verify_totp(code, &decision.eval_command)), "TOTP"),
+        "pin" => (credentials.pin.as_deref().map(verify_pin), "PIN"),
+        "recovery" => (credentials.recovery.as_deref().map(RecoveryAuth::verify), "recovery code"),
+        _ => (Some(Ok(credentials.confirm == Some(true))), "VETO_CONFIRM"),
+    };
+    match verified {
+        Some(Ok(true)) => Ok(label.to_string()),
+        Some(Err(e @ (AuthError::Locked(_) | AuthError::Failed(_) | AuthError::NotAvailable(_)))) => {
+            Err(Outcome::Denied { reason: format!("[veto] {}", e) })
+        }
+        _ => Err(Outcome::Denied { reason: "Verification failed".to_string() }),
+    }
+}
+
+fn verify_totp(code: &str, command: &str) -> Result<bool, AuthError> {
+    if !TotpAuth::new().is_available() {
+        eprintln!("TOTP not configured. Run 'veto auth setup-totp' first.");
+        return Ok(false);
+    }
+    TotpAuth::verify_for(code, Some(command))
+}
+
+fn verify_pin(code: &str) -> Result<bool, AuthError> {
+    let auth = PinAuth::new();
+    if !auth.is_available() {
+        eprintln!("PIN not configured. Run 'veto auth set-pin' first.");
+        return Ok(false);
+    }
+    auth.verify_direct(code)
+}
+
+/// Audit final outcomes; rejections in hooks also go to the deny cache
+fn record(decision: &Decision, outcome: &Outcome) {
+    let command = &decision.eval_command;
+    let level = decision.level();
+    match outcome {
+        Outcome::Allowed { method: Some(method) } => pipeline::record_allowed(command, level, method),
+        Outcome::Denied { .. } => pipeline::record_denied(command, level),
+        Outcome::Rejected { .. } => {
+            pipeline::record_denied(command, level);
+            if decision.prompt.hook {
+                let session = decision.prompt.auth.as_ref().and_then(|a| a.session_id.as_deref());
+                audit::record_denied_command(command, level, session, decision.prompt.config.deny_ttl_minutes());
+            }
+        }
+        Outcome::Ask => pipeline::record_blocked(command, level),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AuthMethod;
+    use crate::rules;
+
+    fn context() -> EvalContext {
+        EvalContext::new(RulesEngine::new(rules::default_rules()), Config::default())
+    }
+
+    #[test]
+    fn test_evaluate_allowed_command() {
+        let decision = evaluate("ls -la", &context());
+        assert!(decision.is_allowed());
+        assert_eq!(authorize(&decision, &Credentials::default()), Outcome::Allowed { method: None });
+    }
+
+    #[test]
+    fn test_evaluate_strips_env_prefix() {
+        let decision = evaluate("VETO_CONFIRM=yes git push origin main", &context());
+        assert_eq!(decision.eval_command, "git push origin main");
+        assert_eq!(decision.level(), RiskLevel::Medium);
+        assert_eq!(decision.primary_method(), Some("confirm"));
+        assert_eq!(decision.env.get("VETO_CONFIRM").map(String::as_str), Some("yes"));
+    }
+
+    #[test]
+    fn test_hook_without_credentials() {
+        let mut decision = evaluate("git push origin main", &context().hook(true));
+        // Whatever the local deny cache holds
+        decision.previously_denied = false;
+        assert_eq!(
+            authorize(&decision, &Credentials::default()),
+            Outcome::CredentialsRequired { methods: vec!["confirm".to_string()], challenge: false }
+        );
+
+        let decision = evaluate("git push origin main", &context().hook(true).can_retry(false));
+        let outcome = request_credentials(&decision, vec!["confirm".to_string()]);
+        assert_eq!(outcome, Outcome::Ask);
+    }
+
+    #[test]
+    fn test_plan_uses_supplied_alternative() {
+        let decision = evaluate("git push origin main", &context().hook(true));
+        let factor = AuthFactor::any_of(vec!["pin".to_string(), "totp".to_string()]);
+        let credentials = Credentials { totp: Some("123456".to_string()), ..Default::default() };
+        assert!(matches!(plan(&decision, &factor, &credentials), Ok(Step::Code(m)) if m == "totp"));
+
+        let factor = AuthFactor::any_of(vec!["telegram".to_string(), "confirm".to_string()]);
+        assert!(matches!(plan(&decision, &factor, &Credentials::default()), Ok(Step::Missing(m)) if m == "confirm"));
+
+        // A lone confirmation goes to the tool's prompt, a chain cannot
+        let mut decision = evaluate("git push origin main", &context().hook(true).can_retry(false));
+        decision.auth_methods.push(AuthFactor::single("dialog"));
+        assert_eq!(
+            request_credentials(&decision, vec!["confirm".to_string()]),
+            Outcome::CredentialsRequired { methods: vec!["confirm".to_string()], challenge: false }
+        );
+    }
+
+    #[test]
+    fn test_confirmation_does_not_mint_grants() {
+        let decision = evaluate("git push origin main", &context().hook(true));
+        let confirmed = Credentials { confirm: Some(true), ..Default::default() };
+        let (outcome, methods) = authorize_chain(&decision, &confirmed);
+        assert!(matches!(outcome, Outcome::Allowed { .. }));
+        assert_eq!(methods, vec!["confirm"]);
+        assert!(!methods.iter().any(|m| held_by_user(m)));
+
+        assert!(["pin", "totp", "recovery", "touchid", "dialog", "telegram"].into_iter().all(held_by_user));
+    }
+
+    #[test]
+    fn test_credentials_from_env_prefix() {
+        let mut prefix = HashMap::new();
+        prefix.insert("VETO_CONFIRM".to_string(), "no".to_string());
+        let credentials = Credentials::from_env(&prefix).with_pin(Some("1234".to_string()));
+        assert_eq!(credentials.confirm, Some(false));
+        assert_eq!(credentials.pin.as_deref(), Some("1234"));
+
+        let decision = evaluate("git push origin main", &context().hook(true));
+        assert_eq!(
+            verify_code(&decision, &credentials, "confirm", &mut None),
+            Err(Outcome::Denied { reason: "Verification failed".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_hardened_credentials() {
+        let mut config = Config::default();
+        config.auth = Some(crate::config::AuthConfig {
+            credentials: Some(crate::config::CredentialsConfig { hardened: Some(true), accept: None }),
+            ..Default::default()
+        });
+        let hardened = || EvalContext::new(RulesEngine::new(rules::default_rules()), config.clone()).hook(true);
+
+        // Above LOW, codes need a challenge and VETO_CONFIRM is ignored
+        let decision = evaluate("git push origin main", &hardened());
+        assert!(decision.requires_challenge);
+        let policy = CredentialPolicy::from_config(&config);
+        let credentials = Credentials {
+            pin: Some("1234".to_string()),
+            confirm: Some(true),
+            recovery: Some("abcde-fghij".to_string()),
+            ..Default::default()
+        }
+        .accepted(&policy, decision.level());
+        assert_eq!(credentials.pin.as_deref(), Some("1234"));
+        assert_eq!(credentials.confirm, None);
+        assert_eq!(credentials.recovery, None);
+        assert!(policy.accepts("confirm", RiskLevel::Low));
+        assert!(!policy.binds_codes(RiskLevel::Low));
+
+        // Outside hooks the user is at the terminal
+        assert!(!evaluate("git push origin main", &hardened().hook(false)).requires_challenge);
+
+        // Per-level lists override the defaults
+        let policy = CredentialPolicy {
+            hardened: true,
+            accept: HashMap::from([("high".to_string(), vec!["totp".to_string(), "response".to_string()])]),
+        };
+        assert!(policy.accepts("totp", RiskLevel::High));
+        assert!(!policy.accepts("pin", RiskLevel::High));
+        assert!(policy.accepts("pin", RiskLevel::Medium));
+    }
+
+    fn hardened_config(method: AuthMethod) -> Config {
+        let mut config = Config::default();
+        config.auth = Some(crate::config::AuthConfig {
+            default: None,
+            levels: Some(HashMap::from([("medium".to_string(), method)])),
+            credentials: Some(crate::config::CredentialsConfig { hardened: Some(true), accept: None }),
+            ..Default::default()
+        });
+        config
+    }
+
+    #[test]
+    fn test_hardened_rejects_unbound_codes() {
+        let pin = Credentials { pin: Some("1234".to_string()), ..Default::default() };
+
+        // A tool that cannot relay a challenge response cannot bind a PIN
+        let config = hardened_config(AuthMethod::Single("pin".to_string()));
+        let context = EvalContext::new(RulesEngine::new(rules::default_rules()), config).hook(true).can_retry(false);
+        let decision = evaluate("git push origin main", &context);
+        assert!(matches!(
+            authorize_chain(&decision, &pin).0,
+            Outcome::Denied { reason } if reason.contains("Hardened mode: PIN")
+        ));
+
+        // One response covers one step: the TOTP of a PIN + TOTP chain
+        // would go unbound
+        let chain = AuthMethod::Multiple(vec![AuthMethod::Single("pin".to_string()), AuthMethod::Single("totp".to_string())]);
+        let context = EvalContext::new(RulesEngine::new(rules::default_rules()), hardened_config(chain)).hook(true);
+        let decision = evaluate("git push origin main", &context);
+        let credentials = Credentials {
+            response: Some("1234482731".to_string()),
+            totp: Some("123456".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            authorize_chain(&decision, &credentials).0,
+            Outcome::Denied { reason } if reason.contains("Hardened mode: TOTP")
+        ));
+    }
+}
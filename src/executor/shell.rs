@@ -1,4 +1,6 @@
-use std::process::{Command, ExitStatus, Stdio};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Output, Stdio};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,6 +31,21 @@ impl ShellExecutor {
         Ok(status)
     }
 
+    /// Run a command with its output captured and stdin closed (for
+    /// frontends that own the terminal, such as `veto mcp`)
+    pub fn execute_captured(
+        &self,
+        command: &str,
+        cwd: Option<&Path>,
+        env: &HashMap<String, String>,
+    ) -> Result<Output, ExecError> {
+        let mut cmd = Command::new(&self.shell);
+        cmd.arg("-c").arg(command).envs(env).stdin(Stdio::null());
+        if let Some(dir) = cwd {
+            cmd.current_dir(dir);
+        }
+        Ok(cmd.output()?)
+    }
 }
 
 impl Default for ShellExecutor {
@@ -47,4 +64,14 @@ mod tests {
         let status = executor.execute("true").unwrap();
         assert!(status.success());
     }
+
+    #[test]
+    fn test_execute_captured() {
+        let executor = ShellExecutor::new();
+        let mut env = HashMap::new();
+        env.insert("VETO_TEST_VAR".to_string(), "hello".to_string());
+        let output = executor.execute_captured("echo $VETO_TEST_VAR; pwd", Some(Path::new("/")), &env).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n/\n");
+    }
 }
@@ -0,0 +1,100 @@
+//! Claude Code hooks (PreToolUse)
+
+use super::{context_from, parse_json, HookAdapter, HookInput, HookResponse};
+
+pub struct ClaudeAdapter;
+
+impl HookAdapter for ClaudeAdapter {
+    fn name(&self) -> &str {
+        "Claude"
+    }
+
+    /// Claude Code sends JSON like:
+    /// {"tool_name": "Bash", "tool_input": {"command": "rm -rf test"}, "cwd": "/path", "session_id": "abc123"}
+    ///
+    /// File operations look like:
+    /// {"tool_name":"Write","tool_input":{"file_path":"/etc/passwd","content":"..."}}
+    /// {"tool_name":"Edit","tool_input":{"file_path":"/etc/shadow","old_string":"...","new_string":"..."}}
+    /// {"tool_name":"Read","tool_input":{"file_path":"/app/.env"}}
+    fn parse_input(&self, input: &str, file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        let json = parse_json(input)?;
+
+        if !file_op {
+            let command = json["tool_input"]["command"]
+                .as_str()
+                .map(String::from)
+                .ok_or("No command found in Claude JSON (expected tool_input.command)")?;
+            let context = context_from(&json, json["tool_name"].as_str().unwrap_or("Bash"));
+            return Ok(HookInput { command, context });
+        }
+
+        let tool_name = json["tool_name"].as_str().unwrap_or("unknown");
+        let path = json["tool_input"]["file_path"]
+            .as_str()
+            .or_else(|| json["tool_input"]["path"].as_str())
+            .or_else(|| json["tool_input"]["notebook_path"].as_str())
+            .ok_or("No path found in Claude file operation JSON")?;
+
+        // Synthetic command for risk evaluation
+        // e.g., "write_file:/etc/passwd" or "edit_file:~/.ssh/authorized_keys"
+        let op = match tool_name {
+            "Write" => "write_file".to_string(),
+            "Edit" | "MultiEdit" | "NotebookEdit" => "edit_file".to_string(),
+            "Read" => "read_file".to_string(),
+            other => other.to_lowercase(),
+        };
+        let command = format!("{}:{}", op, path);
+        let context = context_from(&json, tool_name).with_file_path(path.to_string());
+        Ok(HookInput { command, context })
+    }
+
+    fn allow(&self, method: Option<&str>) -> HookResponse {
+        // Without verification, stay silent so Claude's own permissions apply
+        let Some(method) = method else { return HookResponse::default() };
+
+        // permissionDecision "allow" bypasses the permission prompt
+        HookResponse::json(serde_json::json!({
+            "hookSpecificOutput": {
+                "hookEventName": "PreToolUse",
+                "permissionDecision": "allow",
+                "permissionDecisionReason": format!("Authorized via veto {}", method)
+            }
+        }))
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        // Deny with continue: false, so Claude Code stops completely
+        // without showing its own dialog
+        HookResponse::json(serde_json::json!({
+            "hookSpecificOutput": {
+                "hookEventName": "PreToolUse",
+                "permissionDecision": "deny",
+                "permissionDecisionReason": reason
+            },
+            "continue": false
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_claude_input() {
+        let input = r#"{"tool_name":"Bash","tool_input":{"command":"rm -rf test"},"cwd":"/work","session_id":"abc"}"#;
+        let parsed = ClaudeAdapter.parse_input(input, false).unwrap();
+        assert_eq!(parsed.command, "rm -rf test");
+        assert_eq!(parsed.context.cwd.as_deref(), Some("/work"));
+        assert_eq!(parsed.context.session_id.as_deref(), Some("abc"));
+
+        let input = r#"{"tool_name":"Write","tool_input":{"file_path":"/etc/hosts","content":""}}"#;
+        let parsed = ClaudeAdapter.parse_input(input, true).unwrap();
+        assert_eq!(parsed.command, "write_file:/etc/hosts");
+        assert_eq!(parsed.context.file_path.as_deref(), Some("/etc/hosts"));
+        assert!(ClaudeAdapter.parse_input(input, false).is_err());
+
+        let input = r#"{"tool_name":"Read","tool_input":{"file_path":"/app/.env"}}"#;
+        assert_eq!(ClaudeAdapter.parse_input(input, true).unwrap().command, "read_file:/app/.env");
+    }
+}
@@ -0,0 +1,130 @@
+//! OpenAI Codex CLI hooks (PreToolUse in `~/.codex/hooks.json`)
+//!
+//! Codex sends Claude-style payloads, but its shell tools pass the command
+//! as an argv array and file edits arrive as `apply_patch` patches.
+//! Responses use the same `hookSpecificOutput` format as Claude Code.
+
+use super::{context_from, parse_json, ClaudeAdapter, HookAdapter, HookInput, HookResponse};
+
+pub struct CodexAdapter;
+
+impl HookAdapter for CodexAdapter {
+    fn name(&self) -> &str {
+        "Codex"
+    }
+
+    /// Codex CLI sends JSON like:
+    /// {"tool_name":"shell","tool_input":{"command":["bash","-lc","rm -rf test"],"workdir":"/path"},"cwd":"/path","session_id":"abc"}
+    /// {"tool_name":"apply_patch","tool_input":{"input":"*** Begin Patch\n*** Update File: src/main.rs\n..."}}
+    fn parse_input(&self, input: &str, file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        let json = parse_json(input)?;
+        let tool_input = &json["tool_input"];
+        let tool_name = json["tool_name"].as_str().unwrap_or("shell");
+        let mut context = context_from(&json, tool_name);
+        if let Some(workdir) = tool_input["workdir"].as_str() {
+            context = context.with_cwd(workdir.to_string());
+        }
+
+        if file_op {
+            let patch = tool_input["input"]
+                .as_str()
+                .or_else(|| tool_input["patch"].as_str())
+                .ok_or("No patch found in Codex JSON (expected tool_input.input)")?;
+            let ops = patch_file_ops(patch);
+            let first = ops.first().ok_or("No files found in Codex patch")?;
+            context = context.with_file_path(first.1.clone());
+
+            // Compound commands are evaluated part by part, so every file
+            // in the patch is checked against the file operation rules
+            let command = ops
+                .iter()
+                .map(|(op, path)| format!("{}:{}", op, path))
+                .collect::<Vec<_>>()
+                .join(" && ");
+            return Ok(HookInput { command, context });
+        }
+
+        let command = match &tool_input["command"] {
+            serde_json::Value::String(command) => command.clone(),
+            serde_json::Value::Array(argv) => {
+                let argv: Vec<&str> = argv.iter().filter_map(|a| a.as_str()).collect();
+                argv_to_command(&argv)
+            }
+            _ => return Err("No command found in Codex JSON (expected tool_input.command)".into()),
+        };
+        Ok(HookInput { command, context })
+    }
+
+    fn allow(&self, method: Option<&str>) -> HookResponse {
+        ClaudeAdapter.allow(method)
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        ClaudeAdapter.deny(reason)
+    }
+}
+
+/// `["bash", "-lc", "script"]` → `script`; other argv is joined, quoting
+/// arguments that contain spaces or quotes
+fn argv_to_command(argv: &[&str]) -> String {
+    if let [shell, flag, script] = argv {
+        let shell = shell.rsplit('/').next().unwrap_or(shell);
+        if matches!(shell, "sh" | "bash" | "zsh") && matches!(*flag, "-c" | "-lc") {
+            return script.to_string();
+        }
+    }
+    argv.iter()
+        .map(|a| {
+            if a.is_empty() || a.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+                format!("'{}'", a.replace('\'', "'\\''"))
+            } else {
+                a.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Files touched by an apply_patch patch, as (file operation, path)
+fn patch_file_ops(patch: &str) -> Vec<(&'static str, String)> {
+    patch
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end();
+            if let Some(path) = line.strip_prefix("*** Add File: ") {
+                Some(("write_file", path))
+            } else if let Some(path) = line.strip_prefix("*** Move to: ") {
+                Some(("write_file", path))
+            } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+                Some(("edit_file", path))
+            } else {
+                line.strip_prefix("*** Delete File: ").map(|path| ("edit_file", path))
+            }
+        })
+        .map(|(op, path)| (op, path.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codex_shell() {
+        let input = r#"{"tool_name":"shell","tool_input":{"command":["bash","-lc","rm -rf build"],"workdir":"/w"}}"#;
+        let parsed = CodexAdapter.parse_input(input, false).unwrap();
+        assert_eq!(parsed.command, "rm -rf build");
+        assert_eq!(parsed.context.cwd.as_deref(), Some("/w"));
+
+        assert_eq!(argv_to_command(&["git", "commit", "-m", "fix bug"]), "git commit -m 'fix bug'");
+    }
+
+    #[test]
+    fn test_parse_codex_patch() {
+        let patch = "*** Begin Patch\n*** Update File: src/main.rs\n@@\n-a\n+b\n*** Add File: /etc/cron.d/job\n+x\n*** End Patch";
+        let input = serde_json::json!({ "tool_name": "apply_patch", "tool_input": { "input": patch } });
+        let parsed = CodexAdapter.parse_input(&input.to_string(), true).unwrap();
+        assert_eq!(parsed.command, "edit_file:src/main.rs && write_file:/etc/cron.d/job");
+        assert_eq!(parsed.context.file_path.as_deref(), Some("src/main.rs"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Cursor CLI hooks (beforeShellExecution, beforeReadFile, preToolUse)
+
+use super::{parse_json, HookAdapter, HookInput, HookResponse};
+use crate::auth::AuthContext;
+
+pub struct CursorAdapter;
+
+impl HookAdapter for CursorAdapter {
+    fn name(&self) -> &str {
+        "Cursor CLI"
+    }
+
+    /// Cursor CLI sends JSON like:
+    /// {"command":"rm -rf test","cwd":"/path/to/project"}
+    ///
+    /// File operations look like:
+    /// {"hook_event_name":"beforeReadFile","file_path":"/path/.env","content":"..."}
+    /// {"hook_event_name":"preToolUse","tool_name":"Write","tool_input":{"file_path":"/path/.env"}}
+    fn parse_input(&self, input: &str, file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        let json = parse_json(input)?;
+        let cwd = json["cwd"]
+            .as_str()
+            .or_else(|| json["workspace_roots"][0].as_str())
+            .unwrap_or("")
+            .to_string();
+
+        if !file_op {
+            let command = json["command"]
+                .as_str()
+                .map(String::from)
+                .ok_or("No command found in Cursor JSON (expected command)")?;
+            let context = AuthContext::new().with_cwd(cwd).with_tool_name("Bash".to_string());
+            return Ok(HookInput { command, context });
+        }
+
+        let tool_input = &json["tool_input"];
+        let path = tool_input["file_path"]
+            .as_str()
+            .or_else(|| tool_input["path"].as_str())
+            .or_else(|| tool_input["target_file"].as_str())
+            .or_else(|| json["file_path"].as_str())
+            .ok_or("No path found in Cursor file operation JSON")?;
+
+        let tool_name = match json["tool_name"].as_str() {
+            Some(tool) => tool.to_string(),
+            None if json["hook_event_name"] == "beforeReadFile" || json.get("content").is_some() => "Read".to_string(),
+            None => "unknown".to_string(),
+        };
+        let op = match tool_name.as_str() {
+            "Read" => "read_file".to_string(),
+            "Write" => "write_file".to_string(),
+            "Edit" | "StrReplace" | "MultiEdit" => "edit_file".to_string(),
+            "Delete" => "delete_file".to_string(),
+            other => other.to_lowercase(),
+        };
+
+        let context = AuthContext::new()
+            .with_cwd(cwd)
+            .with_tool_name(tool_name)
+            .with_file_path(path.to_string());
+        Ok(HookInput { command: format!("{}:{}", op, path), context })
+    }
+
+    /// Cursor hooks cannot pass codes back; confirmations go to Cursor's
+    /// own permission prompt instead
+    fn can_retry(&self) -> bool {
+        false
+    }
+
+    fn blocks_pending(&self) -> bool {
+        true
+    }
+
+    fn allow(&self, _method: Option<&str>) -> HookResponse {
+        HookResponse::json(serde_json::json!({ "continue": true, "permission": "allow" }))
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        HookResponse::json(serde_json::json!({
+            "continue": false,
+            "permission": "deny",
+            "user_message": reason,
+            "agent_message": reason
+        }))
+    }
+
+    fn ask(&self, user_message: &str, agent_message: &str) -> HookResponse {
+        HookResponse::json(serde_json::json!({
+            "continue": true,
+            "permission": "ask",
+            "user_message": user_message,
+            "agent_message": agent_message
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shell_command() {
+        let input = CursorAdapter.parse_input(r#"{"command":"rm -rf test","cwd":"/tmp"}"#, false).unwrap();
+        assert_eq!(input.command, "rm -rf test");
+        assert_eq!(input.context.cwd.as_deref(), Some("/tmp"));
+    }
+
+    #[test]
+    fn test_parse_file_ops() {
+        let read = r#"{"hook_event_name":"beforeReadFile","file_path":"/home/u/.ssh/id_rsa","content":"..."}"#;
+        let input = CursorAdapter.parse_input(read, true).unwrap();
+        assert_eq!(input.command, "read_file:/home/u/.ssh/id_rsa");
+        assert_eq!(input.context.file_path.as_deref(), Some("/home/u/.ssh/id_rsa"));
+
+        let edit = r#"{"hook_event_name":"preToolUse","tool_name":"Edit","tool_input":{"file_path":"/app/.env"}}"#;
+        assert_eq!(CursorAdapter.parse_input(edit, true).unwrap().command, "edit_file:/app/.env");
+
+        let write = r#"{"tool_name":"Write","tool_input":{"path":"/etc/hosts"}}"#;
+        assert_eq!(CursorAdapter.parse_input(write, true).unwrap().command, "write_file:/etc/hosts");
+
+        assert!(CursorAdapter.parse_input(r#"{"tool_name":"Write","tool_input":{}}"#, true).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Gemini CLI hooks (BeforeTool)
+
+use super::{context_from, parse_json, HookAdapter, HookInput, HookResponse};
+
+pub struct GeminiAdapter;
+
+impl HookAdapter for GeminiAdapter {
+    fn name(&self) -> &str {
+        "Gemini"
+    }
+
+    /// Gemini CLI sends JSON like:
+    /// {"tool_name":"run_shell_command","tool_input":{"command":"rm -rf test"}}
+    ///
+    /// File operations look like:
+    /// {"tool_name":"write_file","tool_input":{"path":"/etc/passwd","content":"..."}}
+    /// {"tool_name":"edit_file","tool_input":{"path":"/etc/shadow","old_string":"...","new_string":"..."}}
+    /// {"tool_name":"read_file","tool_input":{"absolute_path":"/app/.env"}}
+    fn parse_input(&self, input: &str, file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        let json = parse_json(input)?;
+
+        if !file_op {
+            let command = json["tool_input"]["command"]
+                .as_str()
+                .map(String::from)
+                .ok_or("No command found in Gemini JSON (expected tool_input.command)")?;
+            let context = context_from(&json, json["tool_name"].as_str().unwrap_or("run_shell_command"));
+            return Ok(HookInput { command, context });
+        }
+
+        let tool_name = json["tool_name"].as_str().unwrap_or("unknown");
+        let path = json["tool_input"]["path"]
+            .as_str()
+            .or_else(|| json["tool_input"]["file_path"].as_str())
+            .or_else(|| json["tool_input"]["target_file"].as_str())
+            .or_else(|| json["tool_input"]["absolute_path"].as_str())
+            .ok_or("No path found in Gemini file operation JSON")?;
+
+        // Gemini tool names are already "write_file" / "edit_file" / "read_file"
+        let command = format!("{}:{}", tool_name, path);
+        let context = context_from(&json, tool_name).with_file_path(path.to_string());
+        Ok(HookInput { command, context })
+    }
+
+    /// Gemini only reads the JSON decision, so instructions go in a deny
+    fn blocks_pending(&self) -> bool {
+        true
+    }
+
+    fn allow(&self, method: Option<&str>) -> HookResponse {
+        match method {
+            Some(method) => HookResponse::json(serde_json::json!({
+                "decision": "allow",
+                "reason": format!("Authorized via veto {}", method)
+            })),
+            None => HookResponse::json(serde_json::json!({ "decision": "allow" })),
+        }
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        HookResponse::json(serde_json::json!({
+            "decision": "deny",
+            "reason": reason,
+            "systemMessage": reason
+        }))
+    }
+}
@@ -0,0 +1,309 @@
+//! Adapters defined in `~/.veto/adapters/<name>.toml`
+//!
+//! For agents veto has no built-in support for: JSON pointers say where
+//! the command and context live in the hook payload, and templates say
+//! what to print for each response.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{parse_json, CredentialPrompt, HookAdapter, HookInput, HookResponse};
+use crate::auth::AuthContext;
+use crate::config::get_config_dir;
+
+#[derive(Error, Debug)]
+pub enum AdapterError {
+    #[error("Invalid adapter name: {0}")]
+    InvalidName(String),
+    #[error("Adapter not found: {0}")]
+    NotFound(PathBuf),
+    #[error("Failed to read adapter: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("Failed to parse adapter: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// Adapter definition file
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdapterDef {
+    /// Tool name for messages; defaults to the file name
+    pub name: Option<String>,
+    pub input: InputPaths,
+    /// The agent can relay a code from the user and retry the command
+    #[serde(default = "default_true")]
+    pub can_retry: bool,
+    /// Deny commands waiting on the user instead of explaining on stderr
+    #[serde(default)]
+    pub blocks_pending: bool,
+    #[serde(default)]
+    pub allow: ResponseTemplate,
+    #[serde(default = "default_deny")]
+    pub deny: ResponseTemplate,
+    /// Defaults to `deny`
+    pub ask: Option<ResponseTemplate>,
+    /// Defaults to the message on stderr with exit code 2
+    pub needs_credentials: Option<ResponseTemplate>,
+}
+
+/// JSON pointers (RFC 6901) into the hook payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct InputPaths {
+    pub command: String,
+    pub cwd: Option<String>,
+    pub session_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub file_path: Option<String>,
+    /// Tool name → file operation for `--file-op` (e.g. `Write = "write_file"`)
+    #[serde(default)]
+    pub file_ops: HashMap<String, String>,
+}
+
+/// Output for one response kind
+///
+/// Placeholders: `{reason}`, `{method}`, `{message}`, `{user_message}`,
+/// `{agent_message}`, `{command}`, `{level}`. In `stdout` they are JSON
+/// string-escaped, so `"reason": "{reason}"` stays valid JSON.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ResponseTemplate {
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    #[serde(default)]
+    pub exit_code: i32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_deny() -> ResponseTemplate {
+    ResponseTemplate { stdout: None, stderr: Some("{reason}".to_string()), exit_code: 2 }
+}
+
+impl ResponseTemplate {
+    fn render(&self, vars: &[(&str, &str)]) -> HookResponse {
+        HookResponse {
+            stdout: self.stdout.as_ref().map(|t| fill(t, vars, true)),
+            stderr: self.stderr.as_ref().map(|t| fill(t, vars, false)),
+            exit_code: self.exit_code,
+        }
+    }
+}
+
+/// Substitute `{name}` placeholders in one pass, so values containing
+/// braces are never expanded again
+fn fill(template: &str, vars: &[(&str, &str)], json_escape: bool) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let var = tail
+            .find('}')
+            .and_then(|end| vars.iter().find(|(name, _)| *name == &tail[1..end]).map(|v| (end, v.1)));
+        match var {
+            Some((end, value)) if json_escape => {
+                let quoted = serde_json::Value::from(value).to_string();
+                out.push_str(&quoted[1..quoted.len() - 1]);
+                rest = &tail[end + 1..];
+            }
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A hook adapter driven by an `AdapterDef`
+pub struct GenericAdapter {
+    name: String,
+    def: AdapterDef,
+}
+
+impl GenericAdapter {
+    pub fn new(name: impl Into<String>, def: AdapterDef) -> Self {
+        let name = def.name.clone().unwrap_or_else(|| name.into());
+        Self { name, def }
+    }
+
+    /// Load `~/.veto/adapters/<name>.toml`
+    pub fn load(name: &str) -> Result<Self, AdapterError> {
+        let path = adapter_path(name)?;
+        if !path.is_file() {
+            return Err(AdapterError::NotFound(path));
+        }
+        let def: AdapterDef = toml::from_str(&std::fs::read_to_string(&path)?)?;
+        Ok(Self::new(name, def))
+    }
+}
+
+/// Path of an adapter definition; names are plain file stems
+pub fn adapter_path(name: &str) -> Result<PathBuf, AdapterError> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AdapterError::InvalidName(name.to_string()));
+    }
+    Ok(get_config_dir().join("adapters").join(format!("{}.toml", name)))
+}
+
+impl HookAdapter for GenericAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parse_input(&self, input: &str, file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        let json = parse_json(input)?;
+        let paths = &self.def.input;
+        let field = |pointer: &Option<String>| {
+            pointer.as_deref().and_then(|p| json.pointer(p)).and_then(|v| v.as_str())
+        };
+        let tool_name = field(&paths.tool_name);
+
+        let mut context = AuthContext::new();
+        if let Some(cwd) = field(&paths.cwd) {
+            context = context.with_cwd(cwd);
+        }
+        if let Some(session_id) = field(&paths.session_id) {
+            context = context.with_session_id(session_id);
+        }
+        if let Some(tool) = tool_name {
+            context = context.with_tool_name(tool);
+        }
+
+        if file_op {
+            let path = field(&paths.file_path)
+                .ok_or_else(|| format!("No path found in {} JSON (expected {:?})", self.name, paths.file_path))?;
+            let tool = tool_name.unwrap_or("unknown");
+            let op = paths.file_ops.get(tool).cloned().unwrap_or_else(|| tool.to_lowercase());
+            return Ok(HookInput { command: format!("{}:{}", op, path), context: context.with_file_path(path) });
+        }
+
+        let command = json
+            .pointer(&paths.command)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("No command found in {} JSON (expected {})", self.name, paths.command))?;
+        Ok(HookInput { command: command.to_string(), context })
+    }
+
+    fn can_retry(&self) -> bool {
+        self.def.can_retry
+    }
+
+    fn blocks_pending(&self) -> bool {
+        self.def.blocks_pending
+    }
+
+    fn allow(&self, method: Option<&str>) -> HookResponse {
+        let method = method.unwrap_or("");
+        let reason = if method.is_empty() { String::new() } else { format!("Authorized via veto {}", method) };
+        self.def.allow.render(&[("method", method), ("reason", &reason)])
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        self.def.deny.render(&[("reason", reason), ("message", reason)])
+    }
+
+    fn ask(&self, user_message: &str, agent_message: &str) -> HookResponse {
+        match &self.def.ask {
+            Some(template) => template.render(&[
+                ("user_message", user_message),
+                ("agent_message", agent_message),
+                ("reason", user_message),
+            ]),
+            None => self.deny(user_message),
+        }
+    }
+
+    fn needs_credentials(&self, prompt: &CredentialPrompt) -> HookResponse {
+        let Some(template) = &self.def.needs_credentials else {
+            return HookResponse::stderr(&prompt.message, 2);
+        };
+        let level = prompt.decision.level().to_string();
+        let methods = prompt.methods.join("+");
+        template.render(&[
+            ("message", &prompt.message),
+            ("reason", &prompt.message),
+            ("method", &methods),
+            ("command", &prompt.decision.eval_command),
+            ("level", &level),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEF: &str = r#"
+name = "Acme Agent"
+can_retry = false
+
+[input]
+command = "/call/args/cmd"
+cwd = "/workspace"
+tool_name = "/call/name"
+file_path = "/call/args/file"
+file_ops = { save = "write_file" }
+
+[allow]
+stdout = '{"ok": true}'
+
+[deny]
+stdout = '{"ok": false, "why": "{reason}"}'
+exit_code = 3
+"#;
+
+    fn adapter() -> GenericAdapter {
+        GenericAdapter::new("acme", toml::from_str(DEF).unwrap())
+    }
+
+    #[test]
+    fn test_parse_input_with_pointers() {
+        let adapter = adapter();
+        let input = r#"{"workspace":"/w","call":{"name":"save","args":{"cmd":"rm -rf x","file":"/etc/hosts"}}}"#;
+
+        let parsed = adapter.parse_input(input, false).unwrap();
+        assert_eq!(parsed.command, "rm -rf x");
+        assert_eq!(parsed.context.cwd.as_deref(), Some("/w"));
+        assert_eq!(parsed.context.tool_name.as_deref(), Some("save"));
+
+        let parsed = adapter.parse_input(input, true).unwrap();
+        assert_eq!(parsed.command, "write_file:/etc/hosts");
+        assert!(adapter.parse_input(r#"{"call":{}}"#, false).is_err());
+    }
+
+    #[test]
+    fn test_render_templates() {
+        let adapter = adapter();
+        assert_eq!(adapter.name(), "Acme Agent");
+        assert!(!adapter.can_retry());
+        assert_eq!(adapter.allow(Some("PIN")).stdout.as_deref(), Some(r#"{"ok": true}"#));
+
+        let denied = adapter.deny("bad \"quote\"");
+        assert_eq!(denied.stdout.as_deref(), Some(r#"{"ok": false, "why": "bad \"quote\""}"#));
+        assert_eq!(denied.exit_code, 3);
+        assert_eq!(adapter.ask("ask me", "agent"), adapter.deny("ask me"));
+    }
+
+    #[test]
+    fn test_fill_single_pass() {
+        let vars = [("reason", "{method}"), ("method", "PIN")];
+        assert_eq!(fill(r#"{"r": "{reason}", "m": "{method}", "x": {}}"#, &vars, true), r#"{"r": "{method}", "m": "PIN", "x": {}}"#);
+    }
+
+    #[test]
+    fn test_adapter_names() {
+        assert!(adapter_path("my-agent_2").is_ok());
+        assert!(adapter_path("../evil").is_err());
+        assert!(adapter_path("").is_err());
+    }
+}
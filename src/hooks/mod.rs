@@ -0,0 +1,366 @@
+//! Hook adapters for AI tool integrations
+//!
+//! Each tool (Claude Code, Codex CLI, Gemini CLI, Cursor CLI, OpenCode) sends its own
+//! hook payload and expects its own response format. A `HookAdapter`
+//! parses the payload into a command plus `AuthContext` and renders veto's
+//! outcomes back; `render` maps an `Outcome` onto those responses, so
+//! `veto gate` itself knows nothing about individual tools.
+//!
+//! Agents without built-in support can be described in
+//! `~/.veto/adapters/<name>.toml` (see `generic`).
+
+mod claude;
+mod codex;
+mod cursor;
+mod gemini;
+mod generic;
+mod opencode;
+mod terminal;
+
+pub use claude::ClaudeAdapter;
+pub use codex::CodexAdapter;
+pub use cursor::CursorAdapter;
+pub use gemini::GeminiAdapter;
+pub use generic::{adapter_path, AdapterDef, AdapterError, GenericAdapter};
+pub use opencode::OpenCodeAdapter;
+pub use terminal::TerminalAdapter;
+
+use crate::auth::{takes_response, AuthContext};
+use crate::decision::{Decision, Outcome};
+use crate::pipeline;
+
+/// Command and context extracted from a hook payload
+#[derive(Debug, Clone)]
+pub struct HookInput {
+    pub command: String,
+    pub context: AuthContext,
+}
+
+/// What to print and which exit code to use
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookResponse {
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub exit_code: i32,
+}
+
+impl HookResponse {
+    /// JSON on stdout with exit code 0
+    pub fn json(value: serde_json::Value) -> Self {
+        Self { stdout: Some(value.to_string()), stderr: None, exit_code: 0 }
+    }
+
+    /// A message on stderr
+    pub fn stderr(message: impl Into<String>, exit_code: i32) -> Self {
+        Self { stdout: None, stderr: Some(message.into()), exit_code }
+    }
+
+    /// Print the response and return its exit code
+    pub fn emit(&self) -> i32 {
+        if let Some(out) = &self.stdout {
+            println!("{}", out);
+        }
+        if let Some(err) = &self.stderr {
+            eprintln!("{}", err);
+        }
+        self.exit_code
+    }
+}
+
+/// A command waiting on the user: credentials to relay, a challenge to
+/// answer, or auth setup to fix
+pub struct CredentialPrompt<'a> {
+    pub decision: &'a Decision,
+    /// Auth methods the user should provide codes for (empty if none)
+    pub methods: &'a [String],
+    /// Instructions for the agent
+    pub message: String,
+}
+
+/// Input parsing and response rendering for one AI tool
+pub trait HookAdapter {
+    /// Tool name for messages (e.g. "Claude")
+    fn name(&self) -> &str;
+
+    /// Parse a hook payload (stdin). `file_op` selects the file operation
+    /// hook, which yields a synthetic `write_file:/path` style command.
+    fn parse_input(&self, input: &str, file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>>;
+
+    /// The payload arrives on stdin; otherwise the command is passed as an
+    /// argument
+    fn reads_stdin(&self) -> bool {
+        true
+    }
+
+    /// Invoked by an agent rather than a user at a terminal
+    fn is_hook(&self) -> bool {
+        true
+    }
+
+    /// The agent can relay a code from the user and retry the command
+    fn can_retry(&self) -> bool {
+        true
+    }
+
+    /// Commands waiting on the user are denied outright instead of being
+    /// explained on stderr (for tools that ignore stderr)
+    fn blocks_pending(&self) -> bool {
+        false
+    }
+
+    /// `method` is `None` when the command needed no verification
+    fn allow(&self, method: Option<&str>) -> HookResponse;
+
+    fn deny(&self, reason: &str) -> HookResponse;
+
+    /// Hand the decision to the tool's own permission prompt
+    fn ask(&self, user_message: &str, _agent_message: &str) -> HookResponse {
+        self.deny(user_message)
+    }
+
+    /// The user rejected the command in a prompt
+    fn rejected(&self, _method: &str, reason: &str) -> HookResponse {
+        self.deny(reason)
+    }
+
+    /// Tell the agent what the user has to provide before retrying
+    fn needs_credentials(&self, prompt: &CredentialPrompt) -> HookResponse {
+        HookResponse::stderr(&prompt.message, 2)
+    }
+}
+
+/// Look up a built-in adapter by name
+pub fn builtin(name: &str) -> Option<Box<dyn HookAdapter>> {
+    match name {
+        "claude" => Some(Box::new(ClaudeAdapter)),
+        "codex" => Some(Box::new(CodexAdapter)),
+        "gemini" => Some(Box::new(GeminiAdapter)),
+        "cursor" => Some(Box::new(CursorAdapter)),
+        "opencode" => Some(Box::new(OpenCodeAdapter)),
+        "terminal" => Some(Box::new(TerminalAdapter)),
+        _ => None,
+    }
+}
+
+/// Resolve `--adapter <name>`: a definition in `~/.veto/adapters/`, or
+/// a built-in adapter of that name
+pub fn load(name: &str) -> Result<Box<dyn HookAdapter>, AdapterError> {
+    match GenericAdapter::load(name) {
+        Ok(adapter) => Ok(Box::new(adapter)),
+        Err(AdapterError::NotFound(path)) => builtin(name).ok_or(AdapterError::NotFound(path)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Render an outcome through an adapter
+///
+/// Commands left waiting on the user are audited as denied when the
+/// adapter blocks them outright.
+pub fn render(adapter: &dyn HookAdapter, decision: &Decision, outcome: Outcome) -> HookResponse {
+    let level = decision.level();
+    match outcome {
+        Outcome::Allowed { method } => adapter.allow(method.as_deref()),
+        Outcome::Denied { reason } => adapter.deny(&reason),
+        Outcome::Rejected { method, reason } => adapter.rejected(&method, &reason),
+        Outcome::Ask => {
+            let reason = decision.risk.reason.as_deref().unwrap_or("Operation requires verification");
+            adapter.ask(
+                &format!("{} command requires approval.", level),
+                &format!("{}: {}", reason, decision.eval_command),
+            )
+        }
+        Outcome::Unavailable { message } => {
+            pending(adapter, CredentialPrompt { decision, methods: &[], message })
+        }
+        Outcome::CredentialsRequired { methods, challenge } if !adapter.can_retry() => {
+            let what = code_labels(&methods, challenge);
+            let message = format!(
+                "[veto] {} required, but {} hooks cannot accept codes. Run the command in a terminal with {} or configure dialog/touchid.",
+                what,
+                adapter.name(),
+                retry_prefixes(&methods, challenge)
+            );
+            pipeline::record_denied(&decision.eval_command, level);
+            adapter.deny(&message)
+        }
+        Outcome::CredentialsRequired { methods, challenge } => {
+            let message = match methods.as_slice() {
+                [method] if method == "totp" => format!("[veto] {} command blocked. Ask user in chat for their TOTP code. If provided, retry command with VETO_TOTP=<code> prefix.", level),
+                [method] if method == "pin" => format!("[veto] {} command blocked. Ask user in chat for their PIN code. If provided, retry command with VETO_PIN=<code> prefix.", level),
+                [method] if method == "recovery" => format!("[veto] {} command blocked. Ask user in chat for one of their recovery codes. If provided, retry command with VETO_RECOVERY=<code> prefix.", level),
+                [_] => format!(
+                    "[veto] {} command blocked. Ask user in chat: \"Do you want to allow `{}`?\" If YES, retry command with VETO_CONFIRM=yes prefix.",
+                    level, decision.eval_command
+                ),
+                _ => {
+                    let what = code_labels(&methods, challenge);
+                    format!(
+                        "[veto] {} command blocked ({} required). Ask user in chat for each of them, then retry command once with {} prefix.",
+                        level,
+                        what,
+                        retry_prefixes(&methods, challenge)
+                    )
+                }
+            };
+            pending(adapter, CredentialPrompt { decision, methods: &methods, message })
+        }
+        Outcome::ChallengeIssued { methods } => {
+            // The response covers the first method that takes one
+            let covered = methods.iter().position(|m| takes_response(m)).unwrap_or(0);
+            let mut message = match methods.get(covered).map(String::as_str) {
+                Some("pin") => format!("[veto] {} command blocked (challenge required). Challenge code sent via notification. Ask user to check notification and combine PIN + challenge code (format: PIN followed by challenge). Retry with VETO_RESPONSE=<PIN><challenge> prefix.", level),
+                Some("totp") => format!("[veto] {} command blocked (challenge required). Challenge code sent via notification. Ask user to check notification and combine TOTP code + challenge code (format: TOTP code followed by challenge). Retry with VETO_RESPONSE=<TOTP><challenge> prefix.", level),
+                _ => format!("[veto] {} command blocked (challenge required). Challenge code sent via notification. Ask user to check notification and enter the challenge code. Retry with VETO_RESPONSE=<challenge> prefix.", level),
+            };
+            let others: Vec<String> =
+                methods.iter().enumerate().filter(|(i, _)| *i != covered).map(|(_, m)| m.clone()).collect();
+            if !others.is_empty() {
+                message.push_str(&format!(
+                    " {} also required: add {} to the same retry.",
+                    code_labels(&others, false),
+                    retry_prefixes(&others, false)
+                ));
+            }
+            pending(adapter, CredentialPrompt { decision, methods: &methods, message })
+        }
+    }
+}
+
+/// What the user has to provide for a method
+fn code_label(method: &str, challenge: bool) -> &'static str {
+    match (method, challenge) {
+        ("pin", true) => "PIN+challenge",
+        ("pin", false) => "PIN",
+        ("totp", _) => "TOTP",
+        ("recovery", _) => "Recovery code",
+        ("confirm", false) => "Confirmation",
+        _ => "Challenge confirmation",
+    }
+}
+
+/// Env prefix carrying the code for a method
+pub(crate) fn retry_prefix(method: &str, challenge: bool) -> &'static str {
+    match (method, challenge) {
+        ("pin", true) => "VETO_RESPONSE=<PIN><challenge>",
+        ("pin", false) => "VETO_PIN=<code>",
+        ("totp", _) => "VETO_TOTP=<code>",
+        ("recovery", _) => "VETO_RECOVERY=<code>",
+        ("confirm", false) => "VETO_CONFIRM=yes",
+        _ => "VETO_RESPONSE=<challenge>",
+    }
+}
+
+/// What the user has to provide for all methods (`PIN + TOTP`)
+fn code_labels(methods: &[String], challenge: bool) -> String {
+    unique(methods.iter().map(|m| code_label(m, challenge))).join(" + ")
+}
+
+/// Env prefixes for all methods, for a single retry
+fn retry_prefixes(methods: &[String], challenge: bool) -> String {
+    unique(methods.iter().map(|m| retry_prefix(m, challenge))).join(" ")
+}
+
+fn unique(items: impl Iterator<Item = &'static str>) -> Vec<&'static str> {
+    let mut seen = Vec::new();
+    for item in items {
+        if !seen.contains(&item) {
+            seen.push(item);
+        }
+    }
+    seen
+}
+
+fn pending(adapter: &dyn HookAdapter, prompt: CredentialPrompt) -> HookResponse {
+    if adapter.blocks_pending() {
+        pipeline::record_denied(&prompt.decision.eval_command, prompt.decision.level());
+        adapter.deny(&prompt.message)
+    } else {
+        adapter.needs_credentials(&prompt)
+    }
+}
+
+/// Parse a hook payload as JSON
+fn parse_json(input: &str) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_str(input)?)
+}
+
+/// Context fields most tools send alongside the tool input
+fn context_from(json: &serde_json::Value, tool_name: &str) -> AuthContext {
+    AuthContext::new()
+        .with_cwd(json["cwd"].as_str().unwrap_or("").to_string())
+        .with_session_id(json["session_id"].as_str().unwrap_or("").to_string())
+        .with_tool_name(tool_name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::decision::{evaluate, EvalContext};
+    use crate::rules::{default_rules, RulesEngine};
+
+    fn decision(command: &str) -> Decision {
+        let context = EvalContext::new(RulesEngine::new(default_rules()), Config::default());
+        evaluate(command, &context)
+    }
+
+    #[test]
+    fn test_builtin_adapters() {
+        for name in ["claude", "codex", "gemini", "cursor", "opencode", "terminal"] {
+            assert!(builtin(name).is_some(), "{}", name);
+        }
+        assert!(builtin("unknown").is_none());
+    }
+
+    #[test]
+    fn test_render_credentials_required() {
+        let decision = decision("git push origin main");
+        let outcome = || Outcome::CredentialsRequired { methods: vec!["totp".to_string()], challenge: false };
+
+        let response = render(&ClaudeAdapter, &decision, outcome());
+        assert_eq!(response.exit_code, 2);
+        assert!(response.stderr.unwrap().contains("VETO_TOTP=<code>"));
+
+        let response = render(&TerminalAdapter, &decision, outcome());
+        assert_eq!(response.exit_code, 2);
+        assert!(response.stderr.unwrap().contains("auth_method: totp"));
+    }
+
+    #[test]
+    fn test_render_credential_chain() {
+        let decision = decision("git push origin main");
+        let outcome = || Outcome::CredentialsRequired {
+            methods: vec!["pin".to_string(), "totp".to_string()],
+            challenge: false,
+        };
+
+        let stderr = render(&ClaudeAdapter, &decision, outcome()).stderr.unwrap();
+        assert!(stderr.contains("PIN + TOTP required"));
+        assert!(stderr.contains("VETO_PIN=<code> VETO_TOTP=<code>"));
+
+        let stderr = render(&TerminalAdapter, &decision, outcome()).stderr.unwrap();
+        assert!(stderr.contains("auth_method: pin+totp"));
+        assert!(stderr.contains("VETO_PIN=<code> VETO_TOTP=<code> <command>"));
+    }
+
+    #[test]
+    fn test_render_challenge_issued() {
+        let decision = decision("git push origin main");
+        let outcome = Outcome::ChallengeIssued { methods: vec!["totp".to_string(), "pin".to_string()] };
+
+        let stderr = render(&ClaudeAdapter, &decision, outcome).stderr.unwrap();
+        assert!(stderr.contains("VETO_RESPONSE=<TOTP><challenge>"));
+        assert!(stderr.contains("PIN also required: add VETO_PIN=<code>"));
+    }
+
+    #[test]
+    fn test_render_allowed() {
+        let decision = decision("ls");
+        let allowed = || Outcome::Allowed { method: None };
+        assert_eq!(render(&ClaudeAdapter, &decision, allowed()), HookResponse::default());
+        assert_eq!(
+            render(&GeminiAdapter, &decision, allowed()).stdout.as_deref(),
+            Some(r#"{"decision":"allow"}"#)
+        );
+    }
+}
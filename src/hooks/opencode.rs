@@ -0,0 +1,43 @@
+//! OpenCode plugin (`tool.execute.before`)
+//!
+//! The plugin passes the command as an argument and reads stderr and the
+//! exit code, so there is no payload to parse.
+
+use colored::Colorize;
+
+use super::{HookAdapter, HookInput, HookResponse};
+
+pub struct OpenCodeAdapter;
+
+impl HookAdapter for OpenCodeAdapter {
+    fn name(&self) -> &str {
+        "OpenCode"
+    }
+
+    fn parse_input(&self, _input: &str, _file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        Err("command required (or use --claude/--codex/--gemini/--opencode/--cursor)".into())
+    }
+
+    fn reads_stdin(&self) -> bool {
+        false
+    }
+
+    fn allow(&self, method: Option<&str>) -> HookResponse {
+        match method {
+            Some(method) => HookResponse::stderr(format!("✓ Approved via {}", method).green().to_string(), 0),
+            None => HookResponse::default(),
+        }
+    }
+
+    fn deny(&self, reason: &str) -> HookResponse {
+        HookResponse::stderr(reason.red().to_string(), 2)
+    }
+
+    /// Tell the agent not to retry after an explicit rejection
+    fn rejected(&self, method: &str, _reason: &str) -> HookResponse {
+        HookResponse::stderr(
+            format!("[veto] DENIED. User rejected via {}. STOP_RETRY: Do not attempt this command again.", method),
+            2,
+        )
+    }
+}
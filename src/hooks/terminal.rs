@@ -0,0 +1,60 @@
+//! Plain `veto gate "<command>"` run by a user or a script
+
+use colored::Colorize;
+
+use super::{CredentialPrompt, HookAdapter, HookInput, HookResponse};
+use crate::rules;
+
+pub struct TerminalAdapter;
+
+impl HookAdapter for TerminalAdapter {
+    fn name(&self) -> &str {
+        "terminal"
+    }
+
+    fn parse_input(&self, _input: &str, _file_op: bool) -> Result<HookInput, Box<dyn std::error::Error>> {
+        Err("command required (or use --claude/--codex/--gemini/--opencode/--cursor)".into())
+    }
+
+    fn reads_stdin(&self) -> bool {
+        false
+    }
+
+    /// A user is present, so confirmations are asked interactively
+    fn is_hook(&self) -> bool {
+        false
+    }
+
+    fn allow(&self, method: Option<&str>) -> HookResponse {
+        match method {
+            Some(method) => HookResponse::stderr(format!("✓ Approved via {}", method).green().to_string(), 0),
+            None => HookResponse::default(),
+        }
+    }
+
+    /// Text message with exit code 2 (blocking)
+    fn deny(&self, reason: &str) -> HookResponse {
+        HookResponse::stderr(reason.red().to_string(), 2)
+    }
+
+    fn needs_credentials(&self, prompt: &CredentialPrompt) -> HookResponse {
+        if prompt.methods.is_empty() {
+            return HookResponse::stderr(&prompt.message, 2);
+        }
+
+        let decision = prompt.decision;
+        let reason = decision.risk.reason.as_deref().unwrap_or("Operation requires verification");
+        let vars: Vec<&str> = prompt.methods.iter().map(|m| super::retry_prefix(m, false)).collect();
+        let lines = [
+            "⚠️  AUTH_REQUIRED".red().bold().to_string(),
+            format!("risk_level: {}", decision.level()),
+            format!("reason: {}", reason),
+            format!("command: {}", rules::redact_secrets(&decision.command)),
+            format!("auth_method: {}", prompt.methods.join("+")),
+            String::new(),
+            "Retry with environment variable:".to_string(),
+            format!("  {} <command>", vars.join(" ")),
+        ];
+        HookResponse::stderr(lines.join("\n"), 2)
+    }
+}
@@ -0,0 +1,17 @@
+//! veto - AI operation guardian
+//!
+//! The library exposes veto's policy so other tools can embed it: build an
+//! [`EvalContext`], [`evaluate`] a command into a [`Decision`], then
+//! [`authorize`] it against [`Credentials`] to get an [`Outcome`]. The
+//! `veto` binary is a frontend over the same API.
+
+pub mod audit;
+pub mod auth;
+pub mod config;
+pub mod decision;
+pub mod executor;
+pub mod hooks;
+pub mod pipeline;
+pub mod rules;
+
+pub use decision::{authorize, evaluate, Credentials, Decision, EvalContext, Outcome};
@@ -1,4 +1,4 @@
-use super::{Rules, Rule, Whitelist};
+use super::{NetworkRules, Rules, Rule, ScoringConfig, Whitelist};
 
 pub fn default_rules() -> Rules {
     Rules {
@@ -17,6 +17,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Potentially destructive system command".to_string()),
                 challenge: None,
+                score: None,
             },
             Rule {
                 category: "credentials".to_string(),
@@ -29,8 +30,9 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Credential exposure risk".to_string()),
                 challenge: None,
+                score: None,
             },
-            // File operation rules (Gemini CLI write_file/edit_file)
+            // File operation rules (write_file/edit_file hooks and shell write targets)
             Rule {
                 category: "file-system-critical".to_string(),
                 patterns: vec![
@@ -43,6 +45,50 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Write to critical system/auth file".to_string()),
                 challenge: None,
+                score: None,
+            },
+            // The file keyring, attempt counters and used TOTP steps: reading
+            // them exposes secrets, changing them resets brute-force limits
+            Rule {
+                category: "veto-secrets".to_string(),
+                patterns: vec![
+                    "*_file:~/.veto/secrets*".to_string(),
+                    "read_file:~/.veto/secrets*".to_string(),
+                    "*.veto/secrets*".to_string(),
+                ],
+                paths: vec![],
+                reason: Some("Access to veto secrets".to_string()),
+                challenge: None,
+                score: None,
+            },
+            // veto's own config, rules, deny cache and grants: an agent
+            // editing them could lift its own limits
+            Rule {
+                category: "veto-state".to_string(),
+                patterns: vec![
+                    "*_file:~/.veto".to_string(),
+                    "*_file:~/.veto/*".to_string(),
+                ],
+                paths: vec![],
+                reason: Some("Write to veto configuration or state".to_string()),
+                challenge: None,
+                score: None,
+            },
+            // Read operation rules (read_file hooks); `*_file:` rules only cover writes
+            Rule {
+                category: "file-read-keys".to_string(),
+                patterns: vec![
+                    "read_file:~/.ssh/id_*".to_string(),
+                    "read_file:*id_rsa*".to_string(),
+                    "read_file:*id_ed25519*".to_string(),
+                    "read_file:*id_ecdsa*".to_string(),
+                    "read_file:~/.gnupg/*".to_string(),
+                    "read_file:/etc/shadow".to_string(),
+                ],
+                paths: vec![],
+                reason: Some("Read of private key".to_string()),
+                challenge: None,
+                score: None,
             },
         ],
         high: vec![
@@ -57,6 +103,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Recursive force delete".to_string()),
                 challenge: None,
+                score: None,
             },
             Rule {
                 category: "secrets".to_string(),
@@ -69,6 +116,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Secrets file access".to_string()),
                 challenge: None,
+                score: None,
             },
             Rule {
                 category: "git-destructive".to_string(),
@@ -81,8 +129,9 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Destructive git operation".to_string()),
                 challenge: None,
+                score: None,
             },
-            // File operation rules (Gemini CLI write_file/edit_file)
+            // File operation rules (write_file/edit_file hooks and shell write targets)
             Rule {
                 category: "file-secrets".to_string(),
                 patterns: vec![
@@ -97,6 +146,60 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Write to secrets/credential file".to_string()),
                 challenge: None,
+                score: None,
+            },
+            Rule {
+                category: "file-read-secrets".to_string(),
+                patterns: vec![
+                    "read_file:*.env".to_string(),
+                    "read_file:*.env.*".to_string(),
+                    "read_file:*.pem".to_string(),
+                    "read_file:*.key".to_string(),
+                    "read_file:*.p12".to_string(),
+                    "read_file:*credentials*".to_string(),
+                    "read_file:*secret*".to_string(),
+                    "read_file:~/.aws/*".to_string(),
+                    "read_file:~/.config/gcloud/*".to_string(),
+                    "read_file:~/.azure/*".to_string(),
+                    "read_file:~/.kube/config".to_string(),
+                    "read_file:~/.docker/config.json".to_string(),
+                    "read_file:~/.netrc".to_string(),
+                    "read_file:~/.npmrc".to_string(),
+                    "read_file:~/.pypirc".to_string(),
+                ],
+                paths: vec![],
+                reason: Some("Read of secrets/credential file".to_string()),
+                challenge: None,
+                score: None,
+            },
+            Rule {
+                category: "file-system".to_string(),
+                patterns: vec![
+                    "*_file:/etc/*".to_string(),
+                    "*_file:/usr/*".to_string(),
+                    "*_file:/boot/*".to_string(),
+                    "*_file:/Library/LaunchDaemons/*".to_string(),
+                ],
+                paths: vec![],
+                reason: Some("Write to system file".to_string()),
+                challenge: None,
+                score: None,
+            },
+            Rule {
+                category: "file-shell-config".to_string(),
+                patterns: vec![
+                    "*_file:~/.bashrc".to_string(),
+                    "*_file:~/.bash_profile".to_string(),
+                    "*_file:~/.profile".to_string(),
+                    "*_file:~/.zshrc".to_string(),
+                    "*_file:~/.zprofile".to_string(),
+                    "*_file:~/.zshenv".to_string(),
+                    "*_file:~/.config/fish/*".to_string(),
+                ],
+                paths: vec![],
+                reason: Some("Write to shell startup file".to_string()),
+                challenge: None,
+                score: None,
             },
         ],
         medium: vec![
@@ -111,6 +214,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Recursive delete".to_string()),
                 challenge: None,
+                score: None,
             },
             Rule {
                 category: "git".to_string(),
@@ -122,6 +226,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Git operation that modifies remote".to_string()),
                 challenge: None,
+                score: None,
             },
             Rule {
                 category: "install".to_string(),
@@ -136,6 +241,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Package installation".to_string()),
                 challenge: None,
+                score: None,
             },
         ],
         low: vec![
@@ -147,6 +253,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("File deletion".to_string()),
                 challenge: None,
+                score: None,
             },
             Rule {
                 category: "network".to_string(),
@@ -157,6 +264,7 @@ pub fn default_rules() -> Rules {
                 paths: vec![],
                 reason: Some("Network request".to_string()),
                 challenge: None,
+                score: None,
             },
         ],
         whitelist: Whitelist {
@@ -187,5 +295,34 @@ pub fn default_rules() -> Rules {
             ],
             paths: vec![],
         },
+        network: NetworkRules {
+            allow_domains: vec![],
+            deny_domains: vec![],
+            secret_paths: vec![
+                "~/.ssh/*".to_string(),
+                "~/.gnupg/*".to_string(),
+                "~/.aws/*".to_string(),
+                "~/.config/gcloud/*".to_string(),
+                "~/.azure/*".to_string(),
+                "~/.kube/config".to_string(),
+                "~/.docker/config.json".to_string(),
+                "~/.netrc".to_string(),
+                "~/.npmrc".to_string(),
+                "~/.pypirc".to_string(),
+                "*id_rsa*".to_string(),
+                "*id_ed25519*".to_string(),
+                "*id_ecdsa*".to_string(),
+                "*.pem".to_string(),
+                "*.key".to_string(),
+                "*.p12".to_string(),
+                "*.env".to_string(),
+                "*.env.*".to_string(),
+                "*credentials*".to_string(),
+                "*secret*".to_string(),
+                "/etc/shadow".to_string(),
+                "/etc/passwd".to_string(),
+            ],
+        },
+        scoring: ScoringConfig::default(),
     }
 }
@@ -0,0 +1,418 @@
+//! Compiled rule matcher
+//!
+//! Patterns are compiled at most once per process instead of on every
+//! evaluation. Patterns whose first word is a literal program name
+//! (`git push*`, `rm -rf /`) are indexed by that word, so a command only
+//! runs against the patterns for its own program plus the unindexed ones
+//! (`*AWS_SECRET*`, `*_file:~/.ssh/*`). Buckets are prepared lazily, so a
+//! hook invocation only pays for the buckets its command touches.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use aho_corasick::AhoCorasick;
+use glob::Pattern;
+use regex::Regex;
+
+use super::{RiskLevel, Rule, Rules};
+
+/// Location of a compiled pattern in the rule set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternRef {
+    pub level: RiskLevel,
+    /// Index of the rule within its level
+    pub rule: usize,
+    /// Index of the pattern within the rule
+    pub pattern: usize,
+}
+
+/// A compiled pattern: its anchored regex and a literal every match
+/// must contain, used as a prefilter
+struct Compiled {
+    id: usize,
+    source: String,
+    literal: String,
+    regex: OnceLock<Option<Regex>>,
+}
+
+impl Compiled {
+    fn is_match(&self, text: &str) -> bool {
+        self.regex
+            .get_or_init(|| Regex::new(&self.source).ok())
+            .as_ref()
+            .is_some_and(|re| re.is_match(text))
+    }
+}
+
+/// Patterns sharing an index key, matched together
+///
+/// A bucket is prepared the first time a command hits it: an aho-corasick
+/// automaton over the required literals finds candidate patterns in one
+/// pass, and only candidates compile and run their regex.
+#[derive(Default)]
+struct Bucket {
+    /// Pattern ids and text in precedence order
+    raw: Vec<(usize, String)>,
+    prepared: OnceLock<Prepared>,
+}
+
+struct Prepared {
+    patterns: Vec<Compiled>,
+    /// Index of each pattern with a non-empty literal, in automaton order
+    with_literal: Vec<usize>,
+    /// Patterns without a literal, always candidates
+    always: Vec<usize>,
+    automaton: Option<AhoCorasick>,
+}
+
+impl Bucket {
+    fn push(&mut self, id: usize, pattern: &str) {
+        self.raw.push((id, pattern.to_string()));
+    }
+
+    fn prepare(&self) -> Prepared {
+        let mut prepared = Prepared { patterns: Vec::new(), with_literal: Vec::new(), always: Vec::new(), automaton: None };
+        for (idx, (id, pattern)) in self.raw.iter().enumerate() {
+            let literal = required_literal(pattern);
+            if literal.is_empty() {
+                prepared.always.push(idx);
+            } else {
+                prepared.with_literal.push(idx);
+            }
+            prepared.patterns.push(Compiled {
+                id: *id,
+                source: pattern_regex(pattern),
+                literal,
+                regex: OnceLock::new(),
+            });
+        }
+        prepared.automaton = AhoCorasick::new(
+            prepared.with_literal.iter().map(|&i| &prepared.patterns[i].literal),
+        )
+        .ok();
+        prepared
+    }
+
+    /// Lowest pattern id in this bucket matching the text, among ids
+    /// accepted by `keep`
+    fn first_match(&self, text: &str, keep: &dyn Fn(usize) -> bool) -> Option<usize> {
+        if self.raw.is_empty() {
+            return None;
+        }
+        let prepared = self.prepared.get_or_init(|| self.prepare());
+
+        let mut candidates: Vec<usize> = match &prepared.automaton {
+            Some(ac) => ac
+                .find_overlapping_iter(text)
+                .map(|m| prepared.with_literal[m.pattern().as_usize()])
+                .collect(),
+            // Without an automaton every pattern is a candidate
+            None => prepared.with_literal.clone(),
+        };
+        candidates.extend(&prepared.always);
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        // Pattern order is precedence order
+        candidates
+            .into_iter()
+            .map(|i| &prepared.patterns[i])
+            .find(|p| keep(p.id) && p.is_match(text))
+            .map(|p| p.id)
+    }
+}
+
+/// A pattern set with a program-name index
+#[derive(Default)]
+struct PatternIndex {
+    by_program: HashMap<String, Bucket>,
+    unindexed: Bucket,
+}
+
+impl PatternIndex {
+    fn insert(&mut self, id: usize, pattern: &str) {
+        match index_key(pattern) {
+            Some(key) => self.by_program.entry(key.to_string()).or_default().push(id, pattern),
+            None => self.unindexed.push(id, pattern),
+        }
+    }
+
+    fn first_match(&self, text: &str, keep: &dyn Fn(usize) -> bool) -> Option<usize> {
+        let key = text.split(' ').next().unwrap_or(text);
+        let indexed = self.by_program.get(key).and_then(|b| b.first_match(text, keep));
+        let unindexed = self.unindexed.first_match(text, keep);
+        match (indexed, unindexed) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Rules compiled for matching
+#[derive(Default)]
+pub struct CompiledRules {
+    refs: Vec<PatternRef>,
+    levels: PatternIndex,
+    whitelist: PatternIndex,
+}
+
+impl CompiledRules {
+    pub fn new(rules: &Rules) -> Self {
+        let mut compiled = Self::default();
+
+        let levels: [(RiskLevel, &[Rule]); 4] = [
+            (RiskLevel::Critical, &rules.critical),
+            (RiskLevel::High, &rules.high),
+            (RiskLevel::Medium, &rules.medium),
+            (RiskLevel::Low, &rules.low),
+        ];
+        // Ids are assigned in evaluation order: level, then rule, then
+        // pattern, so the lowest matching id is the first-match result
+        for (level, level_rules) in levels {
+            for (rule_idx, rule) in level_rules.iter().enumerate() {
+                for (pattern_idx, pattern) in rule.patterns.iter().enumerate() {
+                    let id = compiled.refs.len();
+                    compiled.refs.push(PatternRef { level, rule: rule_idx, pattern: pattern_idx });
+                    compiled.levels.insert(id, pattern);
+                }
+            }
+        }
+
+        for (id, pattern) in rules.whitelist.commands.iter().enumerate() {
+            compiled.whitelist.insert(id, pattern);
+        }
+
+        compiled
+    }
+
+    /// First pattern (in critical > high > medium > low order) matching the text
+    pub fn first_match(&self, text: &str) -> Option<PatternRef> {
+        self.first_match_where(text, |_| true)
+    }
+
+    /// Like `first_match`, skipping patterns rejected by `keep`
+    pub fn first_match_where(&self, text: &str, keep: impl Fn(PatternRef) -> bool) -> Option<PatternRef> {
+        self.levels.first_match(text, &|id| keep(self.refs[id])).map(|id| self.refs[id])
+    }
+
+    /// Whether the text matches a whitelist pattern
+    pub fn is_whitelisted(&self, text: &str) -> bool {
+        self.whitelist.first_match(text, &|_| true).is_some()
+    }
+}
+
+/// Literal first word of a pattern, if every match must start with it
+fn index_key(pattern: &str) -> Option<&str> {
+    let (first, rest) = match pattern.split_once(' ') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (pattern, None),
+    };
+    if first.is_empty() {
+        return None;
+    }
+    // Patterns without `*` are compared literally, so any first word works
+    if rest.is_none() && pattern.contains('*') {
+        return None;
+    }
+    if pattern.contains('*') && first.contains(['*', '?', '[']) {
+        return None;
+    }
+    Some(first)
+}
+
+/// Translate a rule pattern to an anchored regex with the same semantics
+/// as `glob::Pattern::matches` (default options)
+fn pattern_regex(pattern: &str) -> String {
+    // Patterns without `*` are exact matches
+    if !pattern.contains('*') {
+        return format!("^{}$", regex::escape(pattern));
+    }
+    // Invalid globs fall back to a substring match of the core text
+    if Pattern::new(pattern).is_err() {
+        return format!("(?s){}", regex::escape(pattern.trim_matches('*')));
+    }
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut re = String::from("(?s)^");
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => {
+                // `**/` matches zero or more leading directories
+                if chars.get(i + 1) == Some(&'*') && chars.get(i + 2) == Some(&'/') {
+                    re.push_str("(?:.*/)?");
+                    i += 3;
+                    continue;
+                }
+                while chars.get(i + 1) == Some(&'*') {
+                    i += 1;
+                }
+                re.push_str(".*");
+            }
+            '?' => re.push('.'),
+            '[' => match class_end(&chars, i) {
+                Some(end) => {
+                    re.push_str(&class_regex(&chars[i + 1..end]));
+                    i = end;
+                }
+                None => re.push_str(&regex::escape("[")),
+            },
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    re.push('$');
+    re
+}
+
+/// Longest literal run every match of the pattern must contain
+fn required_literal(pattern: &str) -> String {
+    if !pattern.contains('*') {
+        return pattern.to_string();
+    }
+    if Pattern::new(pattern).is_err() {
+        return pattern.trim_matches('*').to_string();
+    }
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut longest = String::new();
+    let mut current = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' | '?' | '[' => {
+                if current.len() > longest.len() {
+                    longest = std::mem::take(&mut current);
+                }
+                current.clear();
+                if chars[i] == '[' {
+                    i = class_end(&chars, i).unwrap_or(i);
+                }
+            }
+            c => current.push(c),
+        }
+        i += 1;
+    }
+    if current.len() > longest.len() {
+        longest = current;
+    }
+    longest
+}
+
+/// Index of the `]` closing a character class starting at `start`
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if chars.get(i) == Some(&'!') {
+        i += 1;
+    }
+    // A `]` right after the opening bracket is a literal member
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+    (i..chars.len()).find(|&j| chars[j] == ']')
+}
+
+/// Regex for the inside of a glob character class (`!a-z` etc.)
+fn class_regex(inner: &[char]) -> String {
+    let (negated, members) = match inner.first() {
+        Some('!') => (true, &inner[1..]),
+        _ => (false, inner),
+    };
+    let mut re = String::from(if negated { "[^" } else { "[" });
+    for &c in members {
+        match c {
+            '-' => re.push('-'),
+            '\\' | '[' | ']' | '^' | '&' | '~' => {
+                re.push('\\');
+                re.push(c);
+            }
+            c => re.push(c),
+        }
+    }
+    re.push(']');
+    re
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        Regex::new(&pattern_regex(pattern)).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn test_pattern_regex_matches_glob_semantics() {
+        let cases = [
+            ("git push*", "git push origin main"),
+            ("git push*--force*", "git push origin --force"),
+            ("*_file:~/.ssh/*", "write_file:~/.ssh/authorized_keys"),
+            ("rm -rf /", "rm -rf /"),
+            ("rm -rf /", "rm -rf /tmp"),
+            ("cat *.env*", "cat .env.local"),
+            ("dd if=* of=/dev/*", "dd if=a of=/dev/sda"),
+            ("chmod [0-7]?? *", "chmod 777 x"),
+            ("chmod [!0-6]* x", "chmod 7 x"),
+            ("*(secret)*", "cat (secret).txt"),
+            ("**/x*", "a/b/x1"),
+        ];
+        for (pattern, text) in cases {
+            let glob = Pattern::new(pattern).map(|p| p.matches(text)).unwrap_or(false);
+            assert_eq!(matches(pattern, text), glob, "{} vs {}", pattern, text);
+        }
+    }
+
+    #[test]
+    fn test_literal_patterns_are_exact() {
+        assert!(matches("ls", "ls"));
+        assert!(!matches("ls", "ls -la"));
+        assert!(matches("echo ?", "echo ?"));
+        assert!(!matches("echo ?", "echo a"));
+    }
+
+    #[test]
+    fn test_required_literal() {
+        assert_eq!(required_literal("git push*--force*"), "git push");
+        assert_eq!(required_literal("*_file:~/.ssh/*"), "_file:~/.ssh/");
+        assert_eq!(required_literal("chmod [0-7]?? *"), "chmod ");
+        assert_eq!(required_literal("ls"), "ls");
+        assert_eq!(required_literal("*"), "");
+    }
+
+    #[test]
+    fn test_index_key() {
+        assert_eq!(index_key("git push*"), Some("git"));
+        assert_eq!(index_key("ls"), Some("ls"));
+        assert_eq!(index_key("> /dev/sda*"), Some(">"));
+        assert_eq!(index_key("mkfs*"), None);
+        assert_eq!(index_key("*AWS_SECRET*"), None);
+        assert_eq!(index_key("g?t push*"), None);
+    }
+
+    #[test]
+    fn test_first_match_follows_precedence() {
+        let rule = |category: &str, patterns: &[&str]| Rule {
+            category: category.to_string(),
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            paths: vec![],
+            reason: None,
+            challenge: None,
+            score: None,
+        };
+        let rules = Rules {
+            critical: vec![rule("secrets", &["*AWS_SECRET*"])],
+            high: vec![rule("git-destructive", &["git reset --hard*"]), rule("any-git", &["git *"])],
+            medium: vec![rule("git", &["git push*", "git *"])],
+            ..Default::default()
+        };
+        let compiled = CompiledRules::new(&rules);
+
+        let hit = compiled.first_match("git push origin").unwrap();
+        assert_eq!((hit.level, hit.rule, hit.pattern), (RiskLevel::High, 1, 0));
+
+        let hit = compiled.first_match("git push AWS_SECRET=1").unwrap();
+        assert_eq!(hit.level, RiskLevel::Critical);
+
+        assert!(compiled.first_match("cargo build").is_none());
+    }
+}
@@ -1,7 +1,15 @@
 mod types;
 mod engine;
 mod defaults;
+mod matcher;
+mod parse;
+mod network;
+mod secrets;
+mod scoring;
+mod writes;
 
 pub use types::*;
 pub use engine::*;
 pub use defaults::*;
+pub use network::evaluate_network;
+pub use secrets::{evaluate_env_secrets, evaluate_secrets, redact_secrets};
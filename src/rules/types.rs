@@ -34,6 +34,10 @@ pub struct Rule {
     /// When true, requires challenge code sent via notification
     #[serde(default)]
     pub challenge: Option<bool>,
+    /// Weight this rule contributes in scoring mode
+    /// (defaults to the weight of its level)
+    #[serde(default)]
+    pub score: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -44,6 +48,99 @@ pub struct Whitelist {
     pub paths: Vec<String>,
 }
 
+/// Network egress policy (`[network]` in rules.toml)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NetworkRules {
+    /// Domains that may be contacted without raising the risk level
+    /// (`github.com` also matches subdomains, `*` patterns are globs)
+    #[serde(default)]
+    pub allow_domains: Vec<String>,
+    /// Domains that are always CRITICAL
+    #[serde(default)]
+    pub deny_domains: Vec<String>,
+    /// Local paths treated as secrets when uploaded or piped to the network
+    #[serde(default)]
+    pub secret_paths: Vec<String>,
+}
+
+/// Additive risk scoring (`[scoring]` in rules.toml)
+///
+/// When enabled, the matched rule and command signals are summed into a
+/// score and the risk level comes from `thresholds` instead of the rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    pub enabled: bool,
+    pub weights: ScoreWeights,
+    pub thresholds: ScoreThresholds,
+    /// Words that mark a command as targeting production
+    pub production_keywords: Vec<String>,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weights: ScoreWeights::default(),
+            thresholds: ScoreThresholds::default(),
+            production_keywords: vec!["prod".to_string(), "production".to_string(), "live".to_string()],
+        }
+    }
+}
+
+/// Weight of each scoring signal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    pub rule_critical: u32,
+    pub rule_high: u32,
+    pub rule_medium: u32,
+    pub rule_low: u32,
+    pub sudo: u32,
+    pub recursive: u32,
+    pub force: u32,
+    pub outside_project: u32,
+    pub secrets: u32,
+    pub network: u32,
+    pub production: u32,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        // Rule weights equal the thresholds, so a matched rule alone keeps
+        // its level and signals can only push it higher
+        Self {
+            rule_critical: 90,
+            rule_high: 55,
+            rule_medium: 30,
+            rule_low: 10,
+            sudo: 20,
+            recursive: 10,
+            force: 15,
+            outside_project: 10,
+            secrets: 30,
+            network: 10,
+            production: 25,
+        }
+    }
+}
+
+/// Minimum score for each risk level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreThresholds {
+    pub low: u32,
+    pub medium: u32,
+    pub high: u32,
+    pub critical: u32,
+}
+
+impl Default for ScoreThresholds {
+    fn default() -> Self {
+        Self { low: 10, medium: 30, high: 55, critical: 90 }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Rules {
     #[serde(default)]
@@ -56,6 +153,10 @@ pub struct Rules {
     pub low: Vec<Rule>,
     #[serde(default)]
     pub whitelist: Whitelist,
+    #[serde(default)]
+    pub network: NetworkRules,
+    #[serde(default)]
+    pub scoring: ScoringConfig,
 }
 
 #[derive(Debug, Clone)]
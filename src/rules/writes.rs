@@ -0,0 +1,248 @@
+//! Write-target extraction for shell commands
+//!
+//! `echo x >> ~/.bashrc` is harmless to pattern rules because the
+//! dangerous part is where the output goes. This module finds the files a
+//! command writes to (redirections, `tee`, `sed -i`, `cp`/`mv`, `dd of=`)
+//! or removes (`rm`, `unlink`, `shred`, `truncate`, `mv` sources) so they
+//! can be checked like the `*_file:` operations of editor hooks.
+
+use super::parse::{basename, pipeline, Stage};
+
+/// Redirection operators that write to their target
+const WRITE_REDIRECTS: &[&str] = &[">", ">>", ">|", "&>", "&>>", "<>"];
+
+/// Targets that are never interesting
+const IGNORED_TARGETS: &[&str] = &["/dev/null", "/dev/stdout", "/dev/stderr", "/dev/tty", "-"];
+
+/// A file written by a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteTarget {
+    /// Synthetic operation name (`write_file` or `edit_file`)
+    pub op: &'static str,
+    /// Path as written in the command
+    pub path: String,
+}
+
+impl WriteTarget {
+    fn write(path: impl Into<String>) -> Self {
+        Self { op: "write_file", path: path.into() }
+    }
+
+    fn edit(path: impl Into<String>) -> Self {
+        Self { op: "edit_file", path: path.into() }
+    }
+}
+
+/// Extract every file a (non-compound) command writes to
+pub fn write_targets(command: &str) -> Vec<WriteTarget> {
+    let mut targets = Vec::new();
+
+    for stage in pipeline(command) {
+        for redirect in &stage.redirects {
+            if WRITE_REDIRECTS.contains(&redirect.op.as_str()) {
+                targets.push(WriteTarget::write(redirect.target.clone()));
+            }
+        }
+
+        match stage.program() {
+            Some("tee") => targets.extend(tee_targets(&stage)),
+            Some("sed") => targets.extend(sed_targets(&stage)),
+            Some("cp") => targets.extend(copy_targets(&stage)),
+            Some("mv") => {
+                targets.extend(copy_targets(&stage));
+                targets.extend(removed_targets(&stage, &["-t", "-S"]));
+            }
+            Some("rm") | Some("unlink") => targets.extend(removed_targets(&stage, &[])),
+            Some("shred") => targets.extend(removed_targets(&stage, &["-n", "-s"])),
+            Some("truncate") => targets.extend(removed_targets(&stage, &["-s", "-r"])),
+            Some("dd") => targets.extend(
+                stage
+                    .args()
+                    .iter()
+                    .filter_map(|a| a.strip_prefix("of="))
+                    .map(WriteTarget::write),
+            ),
+            _ => {}
+        }
+    }
+
+    targets.retain(|t| !t.path.is_empty() && !IGNORED_TARGETS.contains(&t.path.as_str()));
+    targets
+}
+
+fn tee_targets(stage: &Stage) -> Vec<WriteTarget> {
+    stage
+        .args()
+        .iter()
+        .filter(|a| !a.starts_with('-'))
+        .map(WriteTarget::write)
+        .collect()
+}
+
+fn sed_targets(stage: &Stage) -> Vec<WriteTarget> {
+    let args = stage.args();
+    let mut in_place = false;
+    let mut has_script = false;
+    let mut positionals = Vec::new();
+    let mut i = 0;
+
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if arg == "--" {
+            positionals.extend(args[i..].iter());
+            break;
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            match long.split_once('=').map(|(n, _)| n).unwrap_or(long) {
+                "in-place" => in_place = true,
+                "expression" | "file" => {
+                    has_script = true;
+                    if !long.contains('=') {
+                        i += 1;
+                    }
+                }
+                _ => {}
+            }
+        } else if arg.starts_with('-') && arg.len() > 1 {
+            // Short options may be clustered (`-Ei`, `-ie`); `-i` takes an
+            // optional attached suffix, `-e`/`-f` take a value
+            for (pos, c) in arg[1..].char_indices() {
+                match c {
+                    'i' => {
+                        in_place = true;
+                        break;
+                    }
+                    'e' | 'f' => {
+                        has_script = true;
+                        if pos + 2 == arg.len() {
+                            i += 1;
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        } else {
+            positionals.push(arg);
+        }
+    }
+
+    if !in_place {
+        return vec![];
+    }
+    let files = if has_script { &positionals[..] } else { positionals.get(1..).unwrap_or(&[]) };
+    files.iter().map(|f| WriteTarget::edit(f.as_str())).collect()
+}
+
+/// Operands of a command that deletes, truncates or moves away files;
+/// `valued` lists the short options taking a separate value
+fn removed_targets(stage: &Stage, valued: &[&str]) -> Vec<WriteTarget> {
+    let args = stage.args();
+    let mut targets = Vec::new();
+    let mut options = true;
+    let mut i = 0;
+
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if options && arg == "--" {
+            options = false;
+        } else if options && valued.contains(&arg.as_str()) {
+            i += 1;
+        } else if !options || !arg.starts_with('-') {
+            targets.push(WriteTarget::write(arg.as_str()));
+        }
+    }
+
+    // `mv a b`: the last operand is the destination, already covered
+    if stage.program() == Some("mv") && !args.iter().any(|a| a == "-t" || a.starts_with("--target-directory=")) {
+        targets.pop();
+    }
+    targets
+}
+
+fn copy_targets(stage: &Stage) -> Vec<WriteTarget> {
+    let args = stage.args();
+    let mut target_dir = None;
+    let mut positionals: Vec<&String> = Vec::new();
+    let mut i = 0;
+
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if arg == "-t" {
+            target_dir = args.get(i).cloned();
+            i += 1;
+        } else if let Some(dir) = arg.strip_prefix("--target-directory=") {
+            target_dir = Some(dir.to_string());
+        } else if !arg.starts_with('-') {
+            positionals.push(arg);
+        }
+    }
+
+    let (dest, sources) = match target_dir {
+        Some(dir) => (dir, &positionals[..]),
+        None => match positionals.split_last() {
+            Some((dest, sources)) if !sources.is_empty() => ((*dest).clone(), sources),
+            _ => return vec![],
+        },
+    };
+
+    // The destination may be a directory: also check the resulting file
+    // path for each source so `cp key ~/.ssh` is treated as `~/.ssh/key`
+    let mut targets = vec![WriteTarget::write(dest.clone())];
+    for source in sources {
+        let name = basename(source.trim_end_matches('/'));
+        if !name.is_empty() {
+            targets.push(WriteTarget::write(format!("{}/{}", dest.trim_end_matches('/'), name)));
+        }
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(command: &str) -> Vec<String> {
+        write_targets(command).into_iter().map(|t| t.path).collect()
+    }
+
+    #[test]
+    fn test_redirection_targets() {
+        assert_eq!(paths("echo foo > ~/.bashrc"), vec!["~/.bashrc"]);
+        assert_eq!(paths("cat x >> ~/.ssh/authorized_keys 2>/dev/null"), vec!["~/.ssh/authorized_keys"]);
+        assert_eq!(paths("make 2>&1 | less"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_tee_and_dd_targets() {
+        assert_eq!(paths("echo 1.2.3.4 evil | sudo tee -a /etc/hosts"), vec!["/etc/hosts"]);
+        assert_eq!(paths("dd if=img.iso of=/dev/sdb bs=4M"), vec!["/dev/sdb"]);
+    }
+
+    #[test]
+    fn test_sed_in_place_targets() {
+        let targets = write_targets("sed -i 's/a/b/' /etc/ssh/sshd_config");
+        assert_eq!(targets, vec![WriteTarget::edit("/etc/ssh/sshd_config")]);
+        assert_eq!(paths("sed -i.bak -e 's/a/b/' a.txt b.txt"), vec!["a.txt", "b.txt"]);
+        assert_eq!(paths("sed 's/a/b/' /etc/hosts"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_copy_targets() {
+        assert_eq!(paths("cp id_rsa.pub ~/.ssh"), vec!["~/.ssh", "~/.ssh/id_rsa.pub"]);
+        assert_eq!(paths("mv -t /etc/cron.d job"), vec!["/etc/cron.d", "/etc/cron.d/job", "job"]);
+        assert_eq!(paths("cp -r src"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_removed_targets() {
+        assert_eq!(paths("rm -f ~/.veto/deny_cache.json"), vec!["~/.veto/deny_cache.json"]);
+        assert_eq!(paths("rm -rf -- -x build"), vec!["-x", "build"]);
+        assert_eq!(paths("truncate -s 0 ~/.veto/audit.log"), vec!["~/.veto/audit.log"]);
+        assert_eq!(paths("shred -n 3 -u key"), vec!["key"]);
+        assert_eq!(paths("mv ~/.veto/grants.json /tmp/"), vec!["/tmp/", "/tmp/grants.json", "~/.veto/grants.json"]);
+    }
+}
@@ -27,6 +27,7 @@ VETO_DIR="${VETO_HOME:-${HOME}/.veto}"
 CLAUDE_SETTINGS="${HOME}/.claude/settings.json"
 OPENCODE_PLUGIN="${HOME}/.opencode/plugins/veto-gate.js"
 CURSOR_HOOKS="${HOME}/.cursor/hooks.json"
+CODEX_HOOKS="${CODEX_HOME:-${HOME}/.codex}/hooks.json"
 
 # Keychain keys used by veto
 KEYCHAIN_KEYS=(
@@ -118,6 +119,44 @@ remove_claude_hooks() {
     success "✓ Claude Code hooks removed"
 }
 
+# Remove veto hooks from Codex CLI hooks.json
+remove_codex_hooks() {
+    if [ ! -f "$CODEX_HOOKS" ]; then
+        return
+    fi
+
+    if ! command -v jq &> /dev/null; then
+        warn "jq not found, cannot auto-remove Codex CLI hooks"
+        warn "Please manually remove veto hooks from ${CODEX_HOOKS}"
+        return
+    fi
+
+    if ! grep -q "veto gate" "$CODEX_HOOKS" 2>/dev/null; then
+        return
+    fi
+
+    info "Removing veto hooks from Codex CLI..."
+
+    local temp_file="${CODEX_HOOKS}.tmp"
+    jq '
+        if .hooks.PreToolUse then
+            .hooks.PreToolUse |= map(
+                select(
+                    (.hooks // []) | all(
+                        (.command // "") | contains("veto gate") | not
+                    )
+                )
+            )
+            | if .hooks.PreToolUse == [] then del(.hooks.PreToolUse) else . end
+            | if .hooks == {} then del(.hooks) else . end
+        else
+            .
+        end
+    ' "$CODEX_HOOKS" > "$temp_file" && mv "$temp_file" "$CODEX_HOOKS"
+
+    success "✓ Codex CLI hooks removed"
+}
+
 # Remove veto plugin from OpenCode
 remove_opencode_plugin() {
     if [ -f "$OPENCODE_PLUGIN" ]; then
@@ -147,13 +186,15 @@ remove_cursor_hooks() {
 
     local temp_file="${CURSOR_HOOKS}.tmp"
     jq '
-        if .hooks.beforeShellExecution then
-            .hooks.beforeShellExecution |= map(
-                select(
-                    (.command // "") | contains("veto gate") | not
+        if .hooks then
+            .hooks |= with_entries(
+                .value |= map(
+                    select(
+                        (.command // "") | contains("veto gate") | not
+                    )
                 )
+                | select(.value != [])
             )
-            | if .hooks.beforeShellExecution == [] then del(.hooks.beforeShellExecution) else . end
             | if .hooks == {} then del(.hooks) else . end
         else
             .
@@ -203,6 +244,9 @@ main() {
     # Remove Claude Code hooks
     remove_claude_hooks
 
+    # Remove Codex CLI hooks
+    remove_codex_hooks
+
     # Remove OpenCode plugin
     remove_opencode_plugin
 